@@ -0,0 +1,82 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+use super::flat::find_route;
+use crate::{AStarError, Graph};
+
+/// Snaps `from_ll` and `to_ll` (each a `(lat, lon)` position) to their nearest
+/// canonical nodes in `g`, then finds the shortest route between them with
+/// [find_route], so callers don't need to know internal node ids.
+///
+/// Returns [AStarError::InvalidReference] with a node id of `0` if `g` has no
+/// canonical node to snap one of the positions to.
+pub fn find_route_between_coords(
+    g: &Graph,
+    from_ll: (f32, f32),
+    to_ll: (f32, f32),
+    step_limit: usize,
+) -> Result<Vec<i64>, AStarError> {
+    let from_id = g
+        .find_nearest_node(from_ll.0, from_ll.1)
+        .ok_or(AStarError::InvalidReference(0))?
+        .id;
+    let to_id = g
+        .find_nearest_node(to_ll.0, to_ll.1)
+        .ok_or(AStarError::InvalidReference(0))?
+        .id;
+
+    find_route(g, from_id, to_id, step_limit, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn simple_graph_fixture() -> Graph {
+        //   200   200   200
+        // 1─────2─────3─────4
+        //       └─────5─────┘
+        //         100    100
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.01, lon: 0.01 },
+                Node { id: 2, osm_id: 2, lat: 0.02, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.03, lon: 0.01 },
+                Node { id: 4, osm_id: 4, lat: 0.04, lon: 0.01 },
+                Node { id: 5, osm_id: 5, lat: 0.03, lon: 0.00 },
+            ],
+            [
+                (1, 2, 200.0),
+                (2, 1, 200.0),
+                (2, 3, 200.0),
+                (2, 5, 100.0),
+                (3, 2, 200.0),
+                (3, 4, 200.0),
+                (4, 3, 200.0),
+                (4, 5, 100.0),
+                (5, 2, 100.0),
+                (5, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn snaps_and_routes() {
+        let g = simple_graph_fixture();
+        assert_eq!(
+            find_route_between_coords(&g, (0.0101, 0.0101), (0.0401, 0.0101), 100),
+            Ok(vec![1_i64, 2, 5, 4])
+        );
+    }
+
+    #[test]
+    fn empty_graph() {
+        let g = Graph::default();
+        assert_eq!(
+            find_route_between_coords(&g, (0.0, 0.0), (1.0, 1.0), 100),
+            Err(AStarError::InvalidReference(0))
+        );
+    }
+}
@@ -20,6 +20,24 @@ pub enum AStarError {
     /// which can result in a denial-of-service. The step limit protects
     /// against resource exhaustion.
     StepLimitExceeded,
+
+    /// [find_route_beam](crate::find_route_beam) exhausted its frontier without reaching
+    /// the destination, but had pruned at least one node along the way - unlike a plain
+    /// empty result, this does NOT mean no route exists, only that the bounded beam
+    /// couldn't find one.
+    BeamGaveUp,
+
+    /// A [ProgressCallback](crate::ProgressCallback) requested cancellation of the search.
+    Cancelled,
+
+    /// [find_route_via](crate::find_route_via) was given more waypoints than
+    /// [MAX_VIA_WAYPOINTS](crate::MAX_VIA_WAYPOINTS) allows.
+    TooManyWaypoints,
+
+    /// No ordering of the waypoints passed to [find_route_via](crate::find_route_via) or
+    /// [trip::optimize](crate::trip::optimize) connects every leg - at least one pair of
+    /// nodes lies in different graph components, so no route between them exists.
+    Unreachable(i64, i64),
 }
 
 impl std::fmt::Display for AStarError {
@@ -27,6 +45,12 @@ impl std::fmt::Display for AStarError {
         match self {
             Self::InvalidReference(node_id) => write!(f, "invalid node: {}", node_id),
             Self::StepLimitExceeded => write!(f, "step limit exceeded"),
+            Self::BeamGaveUp => write!(f, "beam search gave up without finding a route"),
+            Self::Cancelled => write!(f, "search cancelled by the progress callback"),
+            Self::TooManyWaypoints => write!(f, "too many via waypoints"),
+            Self::Unreachable(from, to) => {
+                write!(f, "no route connects {} and {}", from, to)
+            }
         }
     }
 }
@@ -3,7 +3,7 @@
 
 use std::collections::{BinaryHeap, HashMap};
 
-use crate::{earth_distance, AStarError, Edge, Graph};
+use crate::{earth_distance, AStarError, Edge, Graph, ProgressCallback, PROGRESS_CALLBACK_INTERVAL};
 
 #[derive(Debug, Clone, Copy)]
 struct FlatQueueItem {
@@ -65,11 +65,16 @@ fn reconstruct_flat_path(came_from: &HashMap<i64, i64>, mut last: i64) -> Vec<i6
 /// expanding all nodes accessible from the start, which is usually very time-consuming,
 /// especially on large datasets (like the whole planet). The recommended value is
 /// [DEFAULT_STEP_LIMIT](crate::DEFAULT_STEP_LIMIT).
+///
+/// `progress`, if provided, is called every [PROGRESS_CALLBACK_INTERVAL] expanded nodes
+/// with the step count and the heuristic distance remaining to the goal, letting long
+/// planet-scale searches report progress and be cancelled - see [ProgressCallback].
 pub fn find_route(
     g: &Graph,
     from_id: i64,
     to_id: i64,
     step_limit: usize,
+    mut progress: Option<ProgressCallback<'_>>,
 ) -> Result<Vec<i64>, AStarError> {
     assert_ne!(from_id, 0);
     assert_ne!(to_id, 0);
@@ -88,6 +93,16 @@ pub fn find_route(
             .get_node(from_id)
             .ok_or(AStarError::InvalidReference(from_id))?;
 
+        // If the component cache is available, reject impossible routes without
+        // exhausting the whole search space.
+        if let (Some(from_component), Some(to_component)) =
+            (g.component_id(from_id), g.component_id(to_id))
+        {
+            if from_component != to_component {
+                return Ok(vec![]);
+            }
+        }
+
         let initial_distance =
             earth_distance(from_node.lat, from_node.lon, to_node.lat, to_node.lon);
 
@@ -114,6 +129,14 @@ pub fn find_route(
             return Err(AStarError::StepLimitExceeded);
         }
 
+        if steps % PROGRESS_CALLBACK_INTERVAL == 0 {
+            if let Some(ref mut progress) = progress {
+                if progress(steps, item.score - item.cost) {
+                    return Err(AStarError::Cancelled);
+                }
+            }
+        }
+
         for &Edge {
             to: neighbor_id,
             cost: edge_cost,
@@ -149,3 +172,146 @@ pub fn find_route(
 
     return Ok(vec![]);
 }
+
+/// Like [find_route], but bounds memory/time on planet-scale graphs by keeping only the
+/// `beam_width` most promising nodes in the frontier after each expansion, dropping the
+/// rest entirely. This trades guaranteed optimality for bounded resource use: the returned
+/// route may be suboptimal, or missed altogether even though one exists.
+///
+/// `beam_width == 0` disables pruning, making this behave exactly like [find_route].
+///
+/// Returns [AStarError::BeamGaveUp] if the frontier was exhausted after some pruning took
+/// place - unlike [find_route]'s empty result, this does NOT mean no route exists, only
+/// that the bounded beam failed to find one. An empty vector is still returned if the
+/// graph's component cache proves no route can exist, or if the frontier was exhausted
+/// without ever pruning anything.
+pub fn find_route_beam(
+    g: &Graph,
+    from_id: i64,
+    to_id: i64,
+    step_limit: usize,
+    beam_width: usize,
+) -> Result<Vec<i64>, AStarError> {
+    assert_ne!(from_id, 0);
+    assert_ne!(to_id, 0);
+
+    let mut queue: BinaryHeap<FlatQueueItem> = BinaryHeap::default();
+    let mut came_from: HashMap<i64, i64> = HashMap::default();
+    let mut known_costs: HashMap<i64, f32> = HashMap::default();
+    let mut steps: usize = 0;
+    let mut pruned_anything = false;
+
+    let to_node = g
+        .get_node(to_id)
+        .ok_or(AStarError::InvalidReference(to_id))?;
+
+    {
+        let from_node = g
+            .get_node(from_id)
+            .ok_or(AStarError::InvalidReference(from_id))?;
+
+        // If the component cache is available, reject impossible routes without
+        // exhausting the whole search space.
+        if let (Some(from_component), Some(to_component)) =
+            (g.component_id(from_id), g.component_id(to_id))
+        {
+            if from_component != to_component {
+                return Ok(vec![]);
+            }
+        }
+
+        let initial_distance =
+            earth_distance(from_node.lat, from_node.lon, to_node.lat, to_node.lon);
+
+        queue.push(FlatQueueItem {
+            at: from_id,
+            cost: 0.0,
+            score: initial_distance,
+        });
+        known_costs.insert(from_id, 0.0);
+    }
+
+    while let Some(item) = queue.pop() {
+        if item.at == to_id {
+            return Ok(reconstruct_flat_path(&came_from, to_id));
+        }
+
+        // Contrary to the wikipedia definition, we might keep multiple items in the queue for the same node.
+        if item.cost > known_costs.get(&item.at).cloned().unwrap_or(f32::INFINITY) {
+            continue;
+        }
+
+        steps += 1;
+        if steps > step_limit {
+            return Err(AStarError::StepLimitExceeded);
+        }
+
+        for &Edge {
+            to: neighbor_id,
+            cost: edge_cost,
+        } in g.get_edges(item.at)
+        {
+            assert_ne!(neighbor_id, 0);
+
+            // Check if the referred node exists
+            if let Some(neighbor) = g.get_node(neighbor_id) {
+                // Check if this is the cheapest way to the neighbor
+                let neighbor_cost = item.cost + edge_cost;
+                if neighbor_cost
+                    > known_costs
+                        .get(&neighbor_id)
+                        .cloned()
+                        .unwrap_or(f32::INFINITY)
+                {
+                    continue;
+                }
+
+                // Push the new item into the queue
+                came_from.insert(neighbor_id, item.at);
+                known_costs.insert(neighbor_id, neighbor_cost);
+                queue.push(FlatQueueItem {
+                    at: neighbor_id,
+                    cost: neighbor_cost,
+                    score: neighbor_cost
+                        + earth_distance(neighbor.lat, neighbor.lon, to_node.lat, to_node.lon),
+                });
+            }
+        }
+
+        // Prune the frontier down to beam_width, keeping the lowest-score items.
+        // beam_width == 0 means no pruning - fall back to unbounded behavior.
+        if beam_width > 0 && queue.len() > beam_width {
+            pruned_anything = true;
+
+            let mut frontier: Vec<FlatQueueItem> = queue.drain().collect();
+
+            // The goal is never pruned if it's already in the frontier, even if it
+            // wouldn't otherwise make the cut.
+            let goal_index = frontier.iter().position(|candidate| candidate.at == to_id);
+            let goal_item = goal_index.map(|i| frontier.swap_remove(i));
+
+            let keep = if goal_item.is_some() {
+                beam_width - 1
+            } else {
+                beam_width
+            };
+
+            if frontier.len() > keep {
+                if keep > 0 {
+                    frontier.select_nth_unstable_by(keep - 1, |a, b| {
+                        a.score.partial_cmp(&b.score).unwrap()
+                    });
+                }
+                frontier.truncate(keep);
+            }
+
+            frontier.extend(goal_item);
+            queue = frontier.into_iter().collect();
+        }
+    }
+
+    if pruned_anything {
+        return Err(AStarError::BeamGaveUp);
+    }
+    return Ok(vec![]);
+}
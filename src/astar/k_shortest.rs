@@ -0,0 +1,238 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{AStarError, Graph};
+
+use super::find_route;
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    cost: f32,
+    path: Vec<i64>,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost.eq(&other.cost)
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // NOTE: We revert the order of comparison, as lower costs are considered better
+        // ("higher"), and Rust's BinaryHeap is a max-heap.
+        other.cost.partial_cmp(&self.cost)
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.partial_cmp(self).unwrap()
+    }
+}
+
+fn path_cost(g: &Graph, path: &[i64]) -> f32 {
+    path.windows(2).map(|w| g.get_edge(w[0], w[1])).sum()
+}
+
+/// Finds up to `k` loopless shortest paths between two nodes, using
+/// [Yen's algorithm](https://en.wikipedia.org/wiki/Yen%27s_algorithm), built on top of
+/// [Graph::begin_change] to stage and revert the per-candidate edge removals.
+///
+/// The first returned path is the same one [find_route] would return; subsequent paths are the
+/// next-cheapest loopless alternatives, useful for presenting a user with a couple of realistic
+/// route choices instead of just the single optimum. Returns fewer than `k` paths if the graph
+/// doesn't have that many loopless alternatives.
+///
+/// `step_limit` bounds every individual Dijkstra/A* sub-search spawned by this function - see
+/// [find_route] for its meaning. For graphs with turn restrictions, prefer running this
+/// algorithm on a one-way-free fixup of the graph, as with [find_route] itself.
+///
+/// Note there's no separate `find_k_routes` entry point - this function, built on the
+/// spur-node candidate heap above, is it.
+pub fn k_shortest_paths(
+    g: &mut Graph,
+    from_id: i64,
+    to_id: i64,
+    k: usize,
+    step_limit: usize,
+) -> Result<Vec<Vec<i64>>, AStarError> {
+    let mut found: Vec<Vec<i64>> = Vec::new();
+    if k == 0 {
+        return Ok(found);
+    }
+
+    let first = find_route(g, from_id, to_id, step_limit, None)?;
+    if first.is_empty() {
+        return Ok(found);
+    }
+    found.push(first);
+
+    let mut candidates: BinaryHeap<Candidate> = BinaryHeap::new();
+
+    while found.len() < k {
+        let prev_path = found.last().unwrap().clone();
+
+        for i in 0..prev_path.len().saturating_sub(1) {
+            let spur_node = prev_path[i];
+            let root_path = &prev_path[..=i];
+
+            let mut change = g.begin_change();
+
+            // Remove the edge leaving the i-th node of every already-known path sharing this
+            // same root, so the spur search can't just re-discover a known path.
+            for known in found.iter().chain(candidates.iter().map(|c| &c.path)) {
+                if known.len() > i + 1 && &known[..=i] == root_path {
+                    change.remove_edge(known[i], known[i + 1]);
+                }
+            }
+
+            // Remove every root-path node but the spur itself, so the spur path can't loop back
+            // through the root and stays loopless.
+            for &node in &root_path[..root_path.len() - 1] {
+                let neighbors: Vec<i64> =
+                    change.graph().get_edges(node).iter().map(|e| e.to).collect();
+                for neighbor in neighbors {
+                    change.remove_edge(node, neighbor);
+                }
+            }
+
+            let spur_path = find_route(change.graph(), spur_node, to_id, step_limit, None);
+            change.revert();
+
+            if let Ok(spur_path) = spur_path {
+                if !spur_path.is_empty() {
+                    let mut total_path = root_path[..root_path.len() - 1].to_vec();
+                    total_path.extend(spur_path);
+
+                    if !found.contains(&total_path)
+                        && !candidates.iter().any(|c| c.path == total_path)
+                    {
+                        candidates.push(Candidate {
+                            cost: path_cost(g, &total_path),
+                            path: total_path,
+                        });
+                    }
+                }
+            }
+        }
+
+        match candidates.pop() {
+            Some(next) => found.push(next.path),
+            None => break,
+        }
+    }
+
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn test_k_shortest_paths() {
+        //    500   100
+        //  7─────8─────9
+        //  │     │     │
+        //  │400  │300  │100
+        //  │ 200 │ 400 │
+        //  4─────5─────6
+        //  │     │     │
+        //  │600  │500  │100
+        //  │ 100 │ 200 │
+        //  1─────2─────3
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+                Node { id: 2, osm_id: 2, lat: 0.01, lon: 0.00 },
+                Node { id: 3, osm_id: 3, lat: 0.02, lon: 0.00 },
+                Node { id: 4, osm_id: 4, lat: 0.00, lon: 0.01 },
+                Node { id: 5, osm_id: 5, lat: 0.01, lon: 0.01 },
+                Node { id: 6, osm_id: 6, lat: 0.02, lon: 0.01 },
+                Node { id: 7, osm_id: 7, lat: 0.00, lon: 0.02 },
+                Node { id: 8, osm_id: 8, lat: 0.01, lon: 0.02 },
+                Node { id: 9, osm_id: 9, lat: 0.02, lon: 0.02 },
+            ],
+            [
+                (1, 2, 100.0),
+                (1, 4, 600.0),
+                (2, 1, 100.0),
+                (2, 3, 200.0),
+                (2, 5, 500.0),
+                (3, 2, 200.0),
+                (3, 6, 100.0),
+                (4, 1, 600.0),
+                (4, 5, 200.0),
+                (4, 7, 400.0),
+                (5, 2, 500.0),
+                (5, 4, 200.0),
+                (5, 6, 400.0),
+                (5, 8, 300.0),
+                (6, 3, 100.0),
+                (6, 5, 400.0),
+                (6, 9, 100.0),
+                (7, 4, 400.0),
+                (7, 8, 500.0),
+                (8, 5, 300.0),
+                (8, 7, 500.0),
+                (8, 9, 100.0),
+                (9, 6, 100.0),
+                (9, 8, 100.0),
+            ],
+        );
+
+        let paths = k_shortest_paths(&mut g, 1, 8, 3, 1_000).unwrap();
+        assert_eq!(paths[0], vec![1_i64, 2, 3, 6, 9, 8]);
+        assert!(paths.len() > 1);
+
+        // Every returned path must actually be loopless and reach the destination.
+        for path in &paths {
+            assert_eq!(*path.last().unwrap(), 8);
+            let mut seen = path.clone();
+            seen.sort();
+            seen.dedup();
+            assert_eq!(seen.len(), path.len(), "path {:?} has a loop", path);
+        }
+
+        // Costs must be non-decreasing.
+        let costs: Vec<f32> = paths.iter().map(|p| path_cost(&g, p)).collect();
+        for w in costs.windows(2) {
+            assert!(w[0] <= w[1]);
+        }
+    }
+
+    #[test]
+    fn test_k_shortest_paths_fewer_than_k() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0)],
+        );
+
+        let paths = k_shortest_paths(&mut g, 1, 2, 5, 1_000).unwrap();
+        assert_eq!(paths, vec![vec![1_i64, 2]]);
+    }
+
+    #[test]
+    fn test_k_shortest_paths_no_route() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+            ],
+            Vec::<(i64, i64, f32)>::new(),
+        );
+
+        let paths = k_shortest_paths(&mut g, 1, 2, 3, 1_000).unwrap();
+        assert!(paths.is_empty());
+    }
+}
@@ -1,12 +1,20 @@
 // (c) Copyright 2025 Mikołaj Kuranowski
 // SPDX-License-Identifier: MIT
 
+mod between_coords;
 mod error;
 mod flat;
+mod k_shortest;
+mod progress;
+mod via;
 mod without_turn_around;
 
+pub use between_coords::find_route_between_coords;
 pub use error::{AStarError, DEFAULT_STEP_LIMIT};
-pub use flat::find_route;
+pub use flat::{find_route, find_route_beam};
+pub use k_shortest::k_shortest_paths;
+pub use progress::{ProgressCallback, PROGRESS_CALLBACK_INTERVAL};
+pub use via::{find_route_via, MAX_VIA_WAYPOINTS};
 pub use without_turn_around::find_route_without_turn_around;
 
 #[cfg(test)]
@@ -71,14 +79,14 @@ mod tests {
     #[test]
     fn simple() {
         let g = simple_graph_fixture();
-        assert_eq!(find_route(&g, 1, 4, 100), Ok(vec![1_i64, 2, 5, 4]));
+        assert_eq!(find_route(&g, 1, 4, 100, None), Ok(vec![1_i64, 2, 5, 4]));
     }
 
     #[test]
     fn simple_without_turn_around() {
         let g = simple_graph_fixture();
         assert_eq!(
-            find_route_without_turn_around(&g, 1, 4, 100),
+            find_route_without_turn_around(&g, 1, 4, 100, None),
             Ok(vec![1_i64, 2, 5, 4])
         );
     }
@@ -86,14 +94,14 @@ mod tests {
     #[test]
     fn step_limit() {
         let g = simple_graph_fixture();
-        assert_eq!(find_route(&g, 1, 4, 2), Err(AStarError::StepLimitExceeded));
+        assert_eq!(find_route(&g, 1, 4, 2, None), Err(AStarError::StepLimitExceeded));
     }
 
     #[test]
     fn step_limit_without_turn_around() {
         let g = simple_graph_fixture();
         assert_eq!(
-            find_route_without_turn_around(&g, 1, 4, 2),
+            find_route_without_turn_around(&g, 1, 4, 2, None),
             Err(AStarError::StepLimitExceeded)
         );
     }
@@ -199,14 +207,14 @@ mod tests {
     #[test]
     fn shortest_not_optimal() {
         let g = shortest_not_optimal_fixture();
-        assert_eq!(find_route(&g, 1, 8, 100), Ok(vec![1_i64, 2, 3, 6, 9, 8]));
+        assert_eq!(find_route(&g, 1, 8, 100, None), Ok(vec![1_i64, 2, 3, 6, 9, 8]));
     }
 
     #[test]
     fn shortest_not_optimal_without_turn_around() {
         let g = shortest_not_optimal_fixture();
         assert_eq!(
-            find_route_without_turn_around(&g, 1, 8, 100),
+            find_route_without_turn_around(&g, 1, 8, 100, None),
             Ok(vec![1_i64, 2, 3, 6, 9, 8])
         );
     }
@@ -281,15 +289,141 @@ mod tests {
     #[test]
     fn turn_restriction() {
         let g = turn_restriction_fixture();
-        assert_eq!(find_route(&g, 1, 3, 100), Ok(vec![1_i64, 20, 4, 2, 3]));
+        assert_eq!(find_route(&g, 1, 3, 100, None), Ok(vec![1_i64, 20, 4, 2, 3]));
     }
 
     #[test]
     fn turn_restriction_without_turn_around() {
         let g = turn_restriction_fixture();
         assert_eq!(
-            find_route_without_turn_around(&g, 1, 3, 100),
+            find_route_without_turn_around(&g, 1, 3, 100, None),
             Ok(vec![1_i64, 20, 4, 5, 3])
         );
     }
+
+    #[test]
+    fn beam_generous_matches_find_route() {
+        let g = simple_graph_fixture();
+        assert_eq!(find_route_beam(&g, 1, 4, 100, 10), Ok(vec![1_i64, 2, 5, 4]));
+    }
+
+    #[test]
+    fn beam_zero_is_unbounded() {
+        let g = shortest_not_optimal_fixture();
+        assert_eq!(
+            find_route_beam(&g, 1, 8, 100, 0),
+            Ok(vec![1_i64, 2, 3, 6, 9, 8])
+        );
+    }
+
+    #[inline]
+    fn dead_end_trap_fixture() -> Graph {
+        //         100         100
+        //   2─────1─────3─────4
+        //  (dead end)
+        // 2 is a heuristic-appealing dead end; only 1-3-4 actually reaches the goal.
+        Graph::from_iter(
+            [
+                Node {
+                    id: 1,
+                    osm_id: 1,
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+                Node {
+                    id: 2,
+                    osm_id: 2,
+                    lat: 0.0,
+                    lon: 0.5,
+                },
+                Node {
+                    id: 3,
+                    osm_id: 3,
+                    lat: 0.0,
+                    lon: -0.5,
+                },
+                Node {
+                    id: 4,
+                    osm_id: 4,
+                    lat: 0.0,
+                    lon: 1.0,
+                },
+            ],
+            [
+                (1, 2, 100.0),
+                (2, 1, 100.0),
+                (1, 3, 100.0),
+                (3, 1, 100.0),
+                (3, 4, 100.0),
+                (4, 3, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn beam_gives_up_when_trapped_at_dead_end() {
+        // With a beam of 1, node 2 (closer to the goal in a straight line but a dead end)
+        // always out-scores node 3, so the search is pruned down a path that never reaches 4.
+        let g = dead_end_trap_fixture();
+        assert_eq!(
+            find_route_beam(&g, 1, 4, 100, 1),
+            Err(AStarError::BeamGaveUp)
+        );
+    }
+
+    #[test]
+    fn beam_finds_route_once_width_allows_both_branches() {
+        let g = dead_end_trap_fixture();
+        assert_eq!(find_route_beam(&g, 1, 4, 100, 2), Ok(vec![1_i64, 3, 4]));
+    }
+
+    #[inline]
+    fn goal_adjacent_to_decoys_fixture() -> Graph {
+        // 1 has edges to 2, 3 and 4 (cost 10 each) and to 5 (cost 50).
+        // 2, 3, 4 are dead-end decoys lying between 1 and 5 in a straight line,
+        // but only the direct 1-5 edge actually reaches the goal.
+        Graph::from_iter(
+            [
+                Node {
+                    id: 1,
+                    osm_id: 1,
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+                Node {
+                    id: 2,
+                    osm_id: 2,
+                    lat: 0.0,
+                    lon: 0.9,
+                },
+                Node {
+                    id: 3,
+                    osm_id: 3,
+                    lat: 0.0,
+                    lon: 0.8,
+                },
+                Node {
+                    id: 4,
+                    osm_id: 4,
+                    lat: 0.0,
+                    lon: 0.7,
+                },
+                Node {
+                    id: 5,
+                    osm_id: 5,
+                    lat: 0.0,
+                    lon: 1.0,
+                },
+            ],
+            [(1, 2, 10.0), (1, 3, 10.0), (1, 4, 10.0), (1, 5, 50.0)],
+        )
+    }
+
+    #[test]
+    fn beam_never_prunes_the_goal_out_of_the_frontier() {
+        // Decoys 2, 3 and 4 all score lower than 5, so a naive beam of 1 would keep one of
+        // them and drop 5 - but the goal must survive pruning whenever it's already queued.
+        let g = goal_adjacent_to_decoys_fixture();
+        assert_eq!(find_route_beam(&g, 1, 5, 100, 1), Ok(vec![1_i64, 5]));
+    }
 }
@@ -0,0 +1,15 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+/// Callback invoked periodically by [find_route](crate::find_route) and
+/// [find_route_without_turn_around](crate::find_route_without_turn_around) as they expand
+/// nodes, every [PROGRESS_CALLBACK_INTERVAL] steps.
+///
+/// Called with the number of nodes expanded so far and the heuristic (straight-line)
+/// distance remaining to the goal from the node being expanded at that step. Return `true` to
+/// cancel the search early - it will then return
+/// [AStarError::Cancelled](crate::AStarError::Cancelled).
+pub type ProgressCallback<'a> = &'a mut dyn FnMut(usize, f32) -> bool;
+
+/// How often, in expanded nodes, a [ProgressCallback] is invoked.
+pub const PROGRESS_CALLBACK_INTERVAL: usize = 1024;
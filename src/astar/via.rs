@@ -0,0 +1,211 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+use crate::{AStarError, Graph};
+
+use super::find_route;
+
+/// Hard cap on the number of `via` waypoints [find_route_via] will accept. Brute-force
+/// ordering is `O(n!)`, so this bounds the search to a few million permutations in the
+/// worst case rather than letting a caller hang the routing thread.
+pub const MAX_VIA_WAYPOINTS: usize = 10;
+
+/// Sums the cost of every edge along `route`. `route` is empty when [find_route] couldn't
+/// reach the destination at all - that's represented as [f32::INFINITY], same as an
+/// unreachable pair in [matrix](crate::matrix), so permutations relying on it are never
+/// picked as cheapest.
+fn route_cost(g: &Graph, route: &[i64]) -> f32 {
+    if route.is_empty() {
+        return f32::INFINITY;
+    }
+    route.windows(2).map(|w| g.get_edge(w[0], w[1])).sum()
+}
+
+/// Advances `a` to its next lexicographic permutation in place, returning `false` once
+/// every permutation has been produced (leaving `a` as the last, descending, permutation).
+fn next_permutation(a: &mut [usize]) -> bool {
+    if a.len() < 2 {
+        return false;
+    }
+
+    let mut i = a.len() - 1;
+    loop {
+        if i == 0 {
+            return false;
+        }
+        i -= 1;
+        if a[i] < a[i + 1] {
+            break;
+        }
+    }
+
+    let mut j = a.len() - 1;
+    while a[j] <= a[i] {
+        j -= 1;
+    }
+    a.swap(i, j);
+    a[i + 1..].reverse();
+    true
+}
+
+/// Finds a single path from `from_id` to `to_id` passing through every node in `via_ids`,
+/// reordering the via waypoints to minimize total cost.
+///
+/// Builds an `(n+2)×(n+2)` matrix of pairwise leg costs with [find_route] (`n` being
+/// `via_ids.len()`, plus the fixed `from_id`/`to_id` endpoints), then exhaustively tries
+/// every ordering of `via_ids` via [next_permutation], keeping the cheapest. The returned
+/// path is the per-leg [find_route] results stitched together, with the duplicated
+/// junction node dropped between consecutive legs.
+///
+/// Unlike [optimize](crate::trip::optimize), both endpoints are fixed and only the
+/// waypoints in between are reordered - there's no round-trip option and no farthest-
+/// insertion fallback, since [MAX_VIA_WAYPOINTS] keeps the brute force cheap.
+///
+/// Returns [AStarError::InvalidReference] if any leg references a node missing from the
+/// graph, [AStarError::TooManyWaypoints] if `via_ids.len()` exceeds [MAX_VIA_WAYPOINTS],
+/// and [AStarError::Unreachable] if no ordering of the waypoints connects every leg.
+pub fn find_route_via(
+    g: &Graph,
+    from_id: i64,
+    via_ids: &[i64],
+    to_id: i64,
+    step_limit: usize,
+) -> Result<Vec<i64>, AStarError> {
+    if via_ids.len() > MAX_VIA_WAYPOINTS {
+        return Err(AStarError::TooManyWaypoints);
+    }
+
+    if via_ids.is_empty() {
+        return find_route(g, from_id, to_id, step_limit, None);
+    }
+
+    let mut nodes = Vec::with_capacity(via_ids.len() + 2);
+    nodes.push(from_id);
+    nodes.extend_from_slice(via_ids);
+    nodes.push(to_id);
+    let n = nodes.len();
+    let last = n - 1;
+
+    let mut paths = vec![vec![None; n]; n];
+    let mut costs = vec![vec![0.0_f32; n]; n];
+
+    for i in 0..n {
+        for j in 0..n {
+            if i == j {
+                continue;
+            }
+            let route = find_route(g, nodes[i], nodes[j], step_limit, None)?;
+            costs[i][j] = route_cost(g, &route);
+            paths[i][j] = Some(route);
+        }
+    }
+
+    let mut order: Vec<usize> = (1..last).collect();
+    let mut best = order.clone();
+    let mut best_cost = f32::INFINITY;
+
+    loop {
+        let mut full_order = vec![0];
+        full_order.extend(&order);
+        full_order.push(last);
+
+        let cost: f32 = full_order.windows(2).map(|w| costs[w[0]][w[1]]).sum();
+        if cost < best_cost {
+            best_cost = cost;
+            best = order.clone();
+        }
+
+        if !next_permutation(&mut order) {
+            break;
+        }
+    }
+
+    let mut full_order = vec![0];
+    full_order.extend(&best);
+    full_order.push(last);
+
+    if let Some(w) = full_order
+        .windows(2)
+        .find(|w| costs[w[0]][w[1]].is_infinite())
+    {
+        return Err(AStarError::Unreachable(nodes[w[0]], nodes[w[1]]));
+    }
+
+    let mut result = vec![nodes[0]];
+    for w in full_order.windows(2) {
+        let mut segment = paths[w[0]][w[1]].clone().unwrap();
+        segment.remove(0);
+        result.extend(segment);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn line_fixture() -> Graph {
+        // A straight line of 5 nodes, fully connected both ways.
+        //
+        // 1───2───3───4───5
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.01 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.02 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.03 },
+                Node { id: 4, osm_id: 4, lat: 0.0, lon: 0.04 },
+                Node { id: 5, osm_id: 5, lat: 0.0, lon: 0.05 },
+            ],
+            [
+                (1, 2, 100.0),
+                (2, 1, 100.0),
+                (2, 3, 100.0),
+                (3, 2, 100.0),
+                (3, 4, 100.0),
+                (4, 3, 100.0),
+                (4, 5, 100.0),
+                (5, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn reorders_vias_for_cheapest_route() {
+        let g = line_fixture();
+        // Given out of order, the optimal visiting order is still 2, then 3.
+        let route = find_route_via(&g, 1, &[3, 2], 5, 100).unwrap();
+        assert_eq!(route, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn no_vias_is_plain_find_route() {
+        let g = line_fixture();
+        assert_eq!(find_route_via(&g, 1, &[], 3, 100), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn too_many_waypoints_is_rejected() {
+        let g = line_fixture();
+        let vias = vec![1_i64; MAX_VIA_WAYPOINTS + 1];
+        assert_eq!(
+            find_route_via(&g, 1, &vias, 3, 100),
+            Err(AStarError::TooManyWaypoints)
+        );
+    }
+
+    #[test]
+    fn unreachable_via_is_rejected() {
+        // Node 6 is isolated - no ordering of vias can reach it.
+        let mut g = line_fixture();
+        g.set_node(Node { id: 6, osm_id: 6, lat: 1.0, lon: 1.0 });
+        g.compute_components();
+
+        assert_eq!(
+            find_route_via(&g, 1, &[6], 5, 100),
+            Err(AStarError::Unreachable(1, 6))
+        );
+    }
+}
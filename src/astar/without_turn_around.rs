@@ -3,7 +3,7 @@
 
 use std::collections::{BinaryHeap, HashMap};
 
-use crate::{earth_distance, AStarError, Edge, Graph};
+use crate::{earth_distance, AStarError, Edge, Graph, ProgressCallback, PROGRESS_CALLBACK_INTERVAL};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct NodeAndBefore {
@@ -75,11 +75,16 @@ fn reconstruct_came_from_path(
 /// expanding all nodes accessible from the start, which is usually very time-consuming,
 /// especially on large datasets (like the whole planet). The recommended value is
 /// [DEFAULT_STEP_LIMIT](crate::DEFAULT_STEP_LIMIT).
+///
+/// `progress`, if provided, is called every [PROGRESS_CALLBACK_INTERVAL] expanded nodes
+/// with the step count and the heuristic distance remaining to the goal, letting long
+/// planet-scale searches report progress and be cancelled - see [ProgressCallback].
 pub fn find_route_without_turn_around(
     g: &Graph,
     from_id: i64,
     to_id: i64,
     step_limit: usize,
+    mut progress: Option<ProgressCallback<'_>>,
 ) -> Result<Vec<i64>, AStarError> {
     assert_ne!(from_id, 0);
     assert_ne!(to_id, 0);
@@ -103,6 +108,16 @@ pub fn find_route_without_turn_around(
             .get_node(from_id)
             .ok_or(AStarError::InvalidReference(from_id))?;
 
+        // If the component cache is available, reject impossible routes without
+        // exhausting the whole search space.
+        if let (Some(from_component), Some(to_component)) =
+            (g.component_id(from_id), g.component_id(to_id))
+        {
+            if from_component != to_component {
+                return Ok(vec![]);
+            }
+        }
+
         let initial_distance =
             earth_distance(from_node.lat, from_node.lon, to_node.lat, to_node.lon);
 
@@ -130,6 +145,14 @@ pub fn find_route_without_turn_around(
             return Err(AStarError::StepLimitExceeded);
         }
 
+        if steps % PROGRESS_CALLBACK_INTERVAL == 0 {
+            if let Some(ref mut progress) = progress {
+                if progress(steps, item.score - item.cost) {
+                    return Err(AStarError::Cancelled);
+                }
+            }
+        }
+
         for &Edge {
             to: neighbor_id,
             cost: edge_cost,
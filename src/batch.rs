@@ -0,0 +1,74 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! Batch point-to-point routing - computing many independent [find_route] queries at
+//! once, as used by routing clients that need full paths (not just costs, see
+//! [matrix::table](crate::matrix::table)) for a large set of origin/destination pairs.
+
+use rayon::prelude::*;
+
+use crate::{find_route, AStarError, Graph};
+
+/// Computes a [find_route] for every `(from_id, to_id)` pair in `queries`, running them
+/// concurrently via `rayon` rather than in a serial loop, since `&Graph` is read-only
+/// during search and therefore `Sync`. Returns one result per query, in the same order.
+pub fn find_routes(
+    g: &Graph,
+    queries: &[(i64, i64)],
+    step_limit: usize,
+) -> Vec<Result<Vec<i64>, AStarError>> {
+    queries
+        .par_iter()
+        .map(|&(from_id, to_id)| find_route(g, from_id, to_id, step_limit, None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn simple_graph_fixture() -> Graph {
+        //   200   200   200
+        // 1─────2─────3─────4
+        //       └─────5─────┘
+        //         100    100
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.01, lon: 0.01 },
+                Node { id: 2, osm_id: 2, lat: 0.02, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.03, lon: 0.01 },
+                Node { id: 4, osm_id: 4, lat: 0.04, lon: 0.01 },
+                Node { id: 5, osm_id: 5, lat: 0.03, lon: 0.00 },
+            ],
+            [
+                (1, 2, 200.0),
+                (2, 1, 200.0),
+                (2, 3, 200.0),
+                (2, 5, 100.0),
+                (3, 2, 200.0),
+                (3, 4, 200.0),
+                (4, 3, 200.0),
+                (4, 5, 100.0),
+                (5, 2, 100.0),
+                (5, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn routes_every_query() {
+        let g = simple_graph_fixture();
+        let results = find_routes(&g, &[(1, 4), (1, 3)], 100);
+        assert_eq!(results, vec![Ok(vec![1, 2, 5, 4]), Ok(vec![1, 2, 3])]);
+    }
+
+    #[test]
+    fn propagates_per_query_errors() {
+        let g = simple_graph_fixture();
+        let results = find_routes(&g, &[(1, 999), (1, 4)], 100);
+        assert_eq!(results[0], Err(AStarError::InvalidReference(999)));
+        assert_eq!(results[1], Ok(vec![1, 2, 5, 4]));
+    }
+}
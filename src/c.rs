@@ -26,6 +26,12 @@ type CLogCallback = unsafe extern "C" fn(
 );
 type CFlushCallback = unsafe extern "C" fn(arg: *mut c_void);
 
+/// Called with the number of nodes expanded so far and the heuristic distance remaining
+/// to the goal, every [PROGRESS_CALLBACK_INTERVAL](crate::PROGRESS_CALLBACK_INTERVAL)
+/// expanded nodes. Return `true` to cancel the search early - see
+/// [ProgressCallback](crate::ProgressCallback).
+type CProgressCallback = unsafe extern "C" fn(arg: *mut c_void, steps: usize, remaining: f32) -> bool;
+
 struct CLogger {
     callback: CLogCallback,
     flush_callback: Option<CFlushCallback>,
@@ -355,9 +361,27 @@ impl COsmProfile {
         osm::Profile {
             name,
             penalties,
+            penalty_rules: &[],
+            factors: &[],
+            factor_rules: &[],
+            barriers: &[],
+            weight_mode: osm::WeightMode::Distance,
+            speed_profile: &[],
             access,
+            access_disallowed_values: &["no", "private"],
+            access_restricted_values: &[],
+            restricted_access_penalty: 1.0,
+            vehicle_height: None,
+            vehicle_width: None,
+            vehicle_length: None,
+            vehicle_weight: None,
             disallow_motorroad: self.disallow_motorroad,
             disable_restrictions: self.disable_restrictions,
+            u_turn_penalty: 0.0,
+            turn_penalty: 0.0,
+            turn_bias: 1.0,
+            traffic_signal_penalty: 0.0,
+            query_time: None,
         }
     }
 }
@@ -389,6 +413,7 @@ pub struct COsmOptions {
     pub profile: *const COsmProfile,
     pub format: COsmFormat,
     pub bbox: [f32; 4],
+    pub include_metadata: bool,
 }
 
 impl COsmOptions {
@@ -397,6 +422,7 @@ impl COsmOptions {
             profile,
             file_format: self.format.into(),
             bbox: self.bbox,
+            include_metadata: self.include_metadata,
         }
     }
 }
@@ -501,6 +527,19 @@ pub enum CRouteResultType {
     Ok = 0,
     InvalidReference = 1,
     StepLimitExceeded = 2,
+    /// Only returned by [routex_find_route_beam] - the beam search gave up without
+    /// reaching the destination. Unlike [CRouteResultType::Ok] with a zero-length route,
+    /// this does NOT mean no route exists, only that the bounded beam couldn't find one.
+    BeamGaveUp = 3,
+    /// The progress callback passed to [routex_find_route] or
+    /// [routex_find_route_without_turn_around] requested cancellation of the search.
+    Cancelled = 4,
+    /// Only returned by [routex_find_route_via] - `via_ids_len` exceeded
+    /// [MAX_VIA_WAYPOINTS](crate::MAX_VIA_WAYPOINTS).
+    TooManyWaypoints = 5,
+    /// Only returned by [routex_find_route_via] - no ordering of the waypoints connects
+    /// every leg; see [CRouteResultUnreachable] for the disconnected pair.
+    Unreachable = 6,
 }
 
 #[repr(C)]
@@ -516,10 +555,18 @@ pub struct CRouteResultInvalidReference {
     pub invalid_node_id: i64,
 }
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct CRouteResultUnreachable {
+    pub from_id: i64,
+    pub to_id: i64,
+}
+
 #[repr(C)]
 pub union CRouteResultInner {
     pub ok: ManuallyDrop<CRouteResultOk>,
     pub invalid_reference: CRouteResultInvalidReference,
+    pub unreachable: CRouteResultUnreachable,
     pub empty: (),
 }
 
@@ -567,6 +614,36 @@ impl CRouteResult {
             type_: CRouteResultType::StepLimitExceeded,
         }
     }
+
+    fn beam_gave_up() -> Self {
+        CRouteResult {
+            inner: CRouteResultInner { empty: () },
+            type_: CRouteResultType::BeamGaveUp,
+        }
+    }
+
+    fn cancelled() -> Self {
+        CRouteResult {
+            inner: CRouteResultInner { empty: () },
+            type_: CRouteResultType::Cancelled,
+        }
+    }
+
+    fn too_many_waypoints() -> Self {
+        CRouteResult {
+            inner: CRouteResultInner { empty: () },
+            type_: CRouteResultType::TooManyWaypoints,
+        }
+    }
+
+    fn unreachable(from_id: i64, to_id: i64) -> Self {
+        CRouteResult {
+            inner: CRouteResultInner {
+                unreachable: CRouteResultUnreachable { from_id, to_id },
+            },
+            type_: CRouteResultType::Unreachable,
+        }
+    }
 }
 
 #[unsafe(no_mangle)]
@@ -575,12 +652,28 @@ pub unsafe extern "C" fn routex_find_route(
     from_id: i64,
     to_id: i64,
     max_steps: usize,
+    progress: Option<CProgressCallback>,
+    progress_arg: *mut c_void,
 ) -> CRouteResult {
     if let Some(graph) = graph.as_ref() {
-        match find_route(graph, from_id, to_id, max_steps) {
+        let progress_arg = progress_arg as usize; // rust is stupid and `*mut c_void` is not `Send + Sync`
+        let mut progress_closure = progress.map(|callback| {
+            move |steps: usize, remaining: f32| unsafe {
+                callback(progress_arg as *mut c_void, steps, remaining)
+            }
+        });
+        let progress_ref = progress_closure
+            .as_mut()
+            .map(|closure| closure as &mut dyn FnMut(usize, f32) -> bool);
+
+        match find_route(graph, from_id, to_id, max_steps, progress_ref) {
             Ok(nodes) => CRouteResult::ok(nodes),
             Err(astar::AStarError::InvalidReference(ref_)) => CRouteResult::invalid_reference(ref_),
             Err(astar::AStarError::StepLimitExceeded) => CRouteResult::empty(),
+            Err(astar::AStarError::BeamGaveUp) => unreachable!("find_route never gives up"),
+            Err(astar::AStarError::Cancelled) => CRouteResult::cancelled(),
+            Err(astar::AStarError::TooManyWaypoints) => unreachable!("find_route does not support via waypoints"),
+            Err(astar::AStarError::Unreachable(..)) => unreachable!("find_route does not support via waypoints"),
         }
     } else {
         CRouteResult {
@@ -602,12 +695,94 @@ pub unsafe extern "C" fn routex_find_route_without_turn_around(
     from_id: i64,
     to_id: i64,
     max_steps: usize,
+    progress: Option<CProgressCallback>,
+    progress_arg: *mut c_void,
+) -> CRouteResult {
+    if let Some(graph) = graph.as_ref() {
+        let progress_arg = progress_arg as usize; // rust is stupid and `*mut c_void` is not `Send + Sync`
+        let mut progress_closure = progress.map(|callback| {
+            move |steps: usize, remaining: f32| unsafe {
+                callback(progress_arg as *mut c_void, steps, remaining)
+            }
+        });
+        let progress_ref = progress_closure
+            .as_mut()
+            .map(|closure| closure as &mut dyn FnMut(usize, f32) -> bool);
+
+        match find_route_without_turn_around(graph, from_id, to_id, max_steps, progress_ref) {
+            Ok(nodes) => CRouteResult::ok(nodes),
+            Err(astar::AStarError::InvalidReference(ref_)) => CRouteResult::invalid_reference(ref_),
+            Err(astar::AStarError::StepLimitExceeded) => CRouteResult::empty(),
+            Err(astar::AStarError::BeamGaveUp) => unreachable!("find_route_without_turn_around never gives up"),
+            Err(astar::AStarError::Cancelled) => CRouteResult::cancelled(),
+            Err(astar::AStarError::TooManyWaypoints) => unreachable!("find_route_without_turn_around does not support via waypoints"),
+            Err(astar::AStarError::Unreachable(..)) => unreachable!("find_route_without_turn_around does not support via waypoints"),
+        }
+    } else {
+        CRouteResult {
+            inner: CRouteResultInner {
+                ok: ManuallyDrop::new(CRouteResultOk {
+                    nodes: null_mut(),
+                    len: 0,
+                    capacity: 0,
+                }),
+            },
+            type_: CRouteResultType::Ok,
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_find_route_beam(
+    graph: *const Graph,
+    from_id: i64,
+    to_id: i64,
+    max_steps: usize,
+    beam_width: usize,
 ) -> CRouteResult {
     if let Some(graph) = graph.as_ref() {
-        match find_route_without_turn_around(graph, from_id, to_id, max_steps) {
+        match find_route_beam(graph, from_id, to_id, max_steps, beam_width) {
             Ok(nodes) => CRouteResult::ok(nodes),
             Err(astar::AStarError::InvalidReference(ref_)) => CRouteResult::invalid_reference(ref_),
             Err(astar::AStarError::StepLimitExceeded) => CRouteResult::empty(),
+            Err(astar::AStarError::BeamGaveUp) => CRouteResult::beam_gave_up(),
+            Err(astar::AStarError::Cancelled) => unreachable!("find_route_beam does not support a progress callback"),
+            Err(astar::AStarError::TooManyWaypoints) => unreachable!("find_route_beam does not support via waypoints"),
+            Err(astar::AStarError::Unreachable(..)) => unreachable!("find_route_beam does not support via waypoints"),
+        }
+    } else {
+        CRouteResult {
+            inner: CRouteResultInner {
+                ok: ManuallyDrop::new(CRouteResultOk {
+                    nodes: null_mut(),
+                    len: 0,
+                    capacity: 0,
+                }),
+            },
+            type_: CRouteResultType::Ok,
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_find_route_via(
+    graph: *const Graph,
+    from_id: i64,
+    via_ids: *const i64,
+    via_ids_len: usize,
+    to_id: i64,
+    max_steps: usize,
+) -> CRouteResult {
+    if let Some(graph) = graph.as_ref() {
+        let via_ids = slice::from_raw_parts(via_ids, via_ids_len);
+        match find_route_via(graph, from_id, via_ids, to_id, max_steps) {
+            Ok(nodes) => CRouteResult::ok(nodes),
+            Err(astar::AStarError::InvalidReference(ref_)) => CRouteResult::invalid_reference(ref_),
+            Err(astar::AStarError::StepLimitExceeded) => CRouteResult::empty(),
+            Err(astar::AStarError::BeamGaveUp) => unreachable!("find_route_via never gives up"),
+            Err(astar::AStarError::Cancelled) => unreachable!("find_route_via does not support a progress callback"),
+            Err(astar::AStarError::TooManyWaypoints) => CRouteResult::too_many_waypoints(),
+            Err(astar::AStarError::Unreachable(from, to)) => CRouteResult::unreachable(from, to),
         }
     } else {
         CRouteResult {
@@ -644,6 +819,81 @@ pub unsafe extern "C" fn routex_route_result_delete(result: CRouteResult) {
         CRouteResultType::StepLimitExceeded => {
             // Nothing to free
         }
+
+        CRouteResultType::BeamGaveUp => {
+            // Nothing to free
+        }
+
+        CRouteResultType::Cancelled => {
+            // Nothing to free
+        }
+
+        CRouteResultType::TooManyWaypoints => {
+            // Nothing to free
+        }
+
+        CRouteResultType::Unreachable => {
+            // Nothing to free
+        }
+    }
+}
+
+#[repr(C)]
+pub struct CRouteResultsBatch {
+    pub results: *mut CRouteResult,
+    pub len: u32,
+    pub capacity: u32,
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_find_routes_batch(
+    graph: *const Graph,
+    from_ids: *const i64,
+    to_ids: *const i64,
+    len: usize,
+    max_steps: usize,
+) -> CRouteResultsBatch {
+    if let Some(graph) = graph.as_ref() {
+        let from_ids = slice::from_raw_parts(from_ids, len);
+        let to_ids = slice::from_raw_parts(to_ids, len);
+        let queries: Vec<(i64, i64)> =
+            from_ids.iter().copied().zip(to_ids.iter().copied()).collect();
+
+        let mut results: Vec<CRouteResult> = crate::batch::find_routes(graph, &queries, max_steps)
+            .into_iter()
+            .map(|result| match result {
+                Ok(nodes) => CRouteResult::ok(nodes),
+                Err(astar::AStarError::InvalidReference(ref_)) => CRouteResult::invalid_reference(ref_),
+                Err(astar::AStarError::StepLimitExceeded) => CRouteResult::empty(),
+                Err(astar::AStarError::BeamGaveUp) => unreachable!("find_routes never gives up"),
+                Err(astar::AStarError::Cancelled) => unreachable!("find_routes does not support a progress callback"),
+                Err(astar::AStarError::TooManyWaypoints) => unreachable!("find_routes does not support via waypoints"),
+                Err(astar::AStarError::Unreachable(..)) => unreachable!("find_routes does not support via waypoints"),
+            })
+            .collect();
+
+        results.shrink_to_fit();
+        let ptr = results.as_mut_ptr();
+        let out_len = results.len().try_into().expect("batch length overflow");
+        let capacity = results.capacity().try_into().expect("batch capacity overflow");
+        forget(results);
+
+        CRouteResultsBatch { results: ptr, len: out_len, capacity }
+    } else {
+        CRouteResultsBatch { results: null_mut(), len: 0, capacity: 0 }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_route_results_batch_delete(batch: CRouteResultsBatch) {
+    if batch.results.is_null() {
+        return;
+    }
+
+    let results =
+        Vec::from_raw_parts(batch.results, batch.len as usize, batch.capacity as usize);
+    for result in results {
+        routex_route_result_delete(result);
     }
 }
 
@@ -677,6 +927,63 @@ pub unsafe extern "C" fn routex_kd_tree_find_nearest_node(
         .unwrap_or(Node::ZERO)
 }
 
+#[repr(C)]
+pub struct CNodeArray {
+    pub nodes: *mut Node,
+    pub len: u32,
+    pub capacity: u32,
+}
+
+fn leak_node_vec(mut nodes: Vec<Node>) -> CNodeArray {
+    nodes.shrink_to_fit();
+    let ptr = nodes.as_mut_ptr();
+    let len = nodes.len().try_into().expect("node array length overflow");
+    let capacity = nodes
+        .capacity()
+        .try_into()
+        .expect("node array capacity overflow");
+    forget(nodes);
+    CNodeArray { nodes: ptr, len, capacity }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_kd_tree_find_nearest_nodes(
+    kd_tree: *const KDTree,
+    lat: f32,
+    lon: f32,
+    k: usize,
+) -> CNodeArray {
+    match kd_tree.as_ref() {
+        Some(kd) => leak_node_vec(kd.find_nearest_nodes(lat, lon, k)),
+        None => CNodeArray { nodes: null_mut(), len: 0, capacity: 0 },
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_kd_tree_find_nodes_in_bbox(
+    kd_tree: *const KDTree,
+    min_lat: f32,
+    min_lon: f32,
+    max_lat: f32,
+    max_lon: f32,
+) -> CNodeArray {
+    match kd_tree.as_ref() {
+        Some(kd) => leak_node_vec(kd.find_nodes_within_bbox([min_lat, min_lon, max_lat, max_lon])),
+        None => CNodeArray { nodes: null_mut(), len: 0, capacity: 0 },
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn routex_node_array_delete(array: CNodeArray) {
+    if !array.nodes.is_null() {
+        drop(Vec::from_raw_parts(
+            array.nodes,
+            array.len as usize,
+            array.capacity as usize,
+        ));
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn routex_earth_distance(lat1: f32, lon1: f32, lat2: f32, lon2: f32) -> f32 {
     earth_distance(lat1, lon1, lat2, lon2)
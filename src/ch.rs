@@ -0,0 +1,463 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! [Contraction Hierarchies](https://en.wikipedia.org/wiki/Contraction_hierarchies) -
+//! a preprocessing scheme that trades a (potentially expensive) one-time preprocessing
+//! step for much faster repeated shortest-path queries, at the cost of memory used by
+//! the extra shortcut edges.
+//!
+//! Use [preprocess] to turn a [Graph] into a [ContractedGraph], then [find_route] to
+//! query it. Preprocessing pays off only when many queries are run against the same
+//! (static) graph - for one-off routes, use [find_route](crate::find_route) instead.
+//!
+//! [preprocess] orders nodes by edge difference, contracting each in turn with witness
+//! searches to decide which shortcuts are actually needed; [find_route] then answers
+//! queries against the contracted graph with a bidirectional search meeting in the middle.
+//! `preprocess`/`find_route` are free functions here rather than methods on `ContractedGraph`,
+//! matching how [crate::find_route] itself is a free function over a plain [Graph].
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{AStarError, Graph};
+
+/// Maximum number of nodes settled by a witness-path search run during contraction.
+/// Witness searches that exceed this limit are assumed to have found no cheaper
+/// path, which is always safe (it just results in an unnecessary shortcut).
+const WITNESS_SEARCH_SETTLED_LIMIT: usize = 50;
+
+/// A directed connection between two nodes in a [ContractedGraph].
+///
+/// Mirrors [Edge](crate::Edge), but additionally remembers the node contracted to
+/// create this edge (if any), so that shortcuts can be unpacked back into the
+/// sequence of original nodes they represent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CHEdge {
+    to: i64,
+    cost: f32,
+    /// The node contracted to produce this edge as a shortcut, or `None` if this
+    /// edge comes directly from the original [Graph].
+    via: Option<i64>,
+}
+
+/// A [Graph] preprocessed by [preprocess] for fast repeated shortest-path queries.
+#[derive(Debug, Clone, Default)]
+pub struct ContractedGraph {
+    /// Contraction rank of every node - lower ranks are contracted first.
+    rank: HashMap<i64, u32>,
+
+    /// Every edge (original and shortcuts) from a node, regardless of rank,
+    /// used only to unpack shortcuts into the original node sequence.
+    all_edges: HashMap<i64, Vec<CHEdge>>,
+
+    /// Edges usable by a forward search - from a node to a higher-ranked node.
+    up: HashMap<i64, Vec<CHEdge>>,
+
+    /// Edges usable by a backward search - from a node to a higher-ranked node,
+    /// in the reversed graph.
+    down: HashMap<i64, Vec<CHEdge>>,
+}
+
+fn add_edge(adjacency: &mut HashMap<i64, Vec<CHEdge>>, from: i64, edge: CHEdge) {
+    let edges = adjacency.entry(from).or_default();
+    if let Some(existing) = edges.iter_mut().find(|e| e.to == edge.to) {
+        if edge.cost < existing.cost {
+            *existing = edge;
+        }
+    } else {
+        edges.push(edge);
+    }
+}
+
+fn remove_edge(adjacency: &mut HashMap<i64, Vec<CHEdge>>, from: i64, to: i64) {
+    if let Some(edges) = adjacency.get_mut(&from) {
+        edges.retain(|e| e.to != to);
+    }
+}
+
+/// Runs a Dijkstra search from `from` restricted to nodes not in `contracted` and not
+/// equal to `avoid`, stopping as soon as `to` is settled, `max_cost` is exceeded, or
+/// [WITNESS_SEARCH_SETTLED_LIMIT] nodes have been settled. Returns the shortest distance
+/// found, or [f32::INFINITY] if none of the above turned up a cheap enough path - in
+/// which case the caller must conservatively assume a shortcut is necessary.
+fn witness_distance(
+    out_edges: &HashMap<i64, Vec<CHEdge>>,
+    from: i64,
+    to: i64,
+    avoid: i64,
+    contracted: &HashSet<i64>,
+    max_cost: f32,
+) -> f32 {
+    let mut known_costs: HashMap<i64, f32> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(ordered_float::OrderedCost, i64)>> = BinaryHeap::new();
+
+    known_costs.insert(from, 0.0);
+    queue.push(Reverse((ordered_float::OrderedCost(0.0), from)));
+
+    let mut settled = 0usize;
+
+    while let Some(Reverse((ordered_float::OrderedCost(cost), node))) = queue.pop() {
+        if cost > known_costs.get(&node).copied().unwrap_or(f32::INFINITY) {
+            continue;
+        }
+
+        if node == to {
+            return cost;
+        }
+
+        settled += 1;
+        if settled > WITNESS_SEARCH_SETTLED_LIMIT || cost > max_cost {
+            return f32::INFINITY;
+        }
+
+        for edge in out_edges.get(&node).map(Vec::as_slice).unwrap_or_default() {
+            if edge.to == avoid || contracted.contains(&edge.to) {
+                continue;
+            }
+
+            let neighbor_cost = cost + edge.cost;
+            if neighbor_cost > max_cost {
+                continue;
+            }
+            if neighbor_cost < known_costs.get(&edge.to).copied().unwrap_or(f32::INFINITY) {
+                known_costs.insert(edge.to, neighbor_cost);
+                queue.push(Reverse((ordered_float::OrderedCost(neighbor_cost), edge.to)));
+            }
+        }
+    }
+
+    f32::INFINITY
+}
+
+/// Computes the shortcuts required to contract `node` out of the graph represented by
+/// `out_edges`/`in_edges`, without mutating anything.
+fn shortcuts_for(
+    out_edges: &HashMap<i64, Vec<CHEdge>>,
+    in_edges: &HashMap<i64, Vec<CHEdge>>,
+    contracted: &HashSet<i64>,
+    node: i64,
+) -> Vec<(i64, i64, f32)> {
+    let mut shortcuts = Vec::new();
+
+    let predecessors = in_edges.get(&node).map(Vec::as_slice).unwrap_or_default();
+    let successors = out_edges.get(&node).map(Vec::as_slice).unwrap_or_default();
+
+    for pred in predecessors {
+        if contracted.contains(&pred.to) {
+            continue;
+        }
+
+        for succ in successors {
+            if succ.to == pred.to || contracted.contains(&succ.to) {
+                continue;
+            }
+
+            let max_cost = pred.cost + succ.cost;
+            let witness = witness_distance(out_edges, pred.to, succ.to, node, contracted, max_cost);
+            if witness > max_cost {
+                shortcuts.push((pred.to, succ.to, max_cost));
+            }
+        }
+    }
+
+    shortcuts
+}
+
+/// The priority used to order node contraction: the edge difference (shortcuts added
+/// minus edges removed). Nodes whose contraction adds the fewest shortcuts relative to
+/// the edges they remove are contracted first, keeping the hierarchy shallow.
+fn priority_of(
+    out_edges: &HashMap<i64, Vec<CHEdge>>,
+    in_edges: &HashMap<i64, Vec<CHEdge>>,
+    contracted: &HashSet<i64>,
+    node: i64,
+) -> i32 {
+    let shortcuts = shortcuts_for(out_edges, in_edges, contracted, node).len() as i32;
+    let removed = (out_edges.get(&node).map(Vec::len).unwrap_or(0)
+        + in_edges.get(&node).map(Vec::len).unwrap_or(0)) as i32;
+    shortcuts - removed
+}
+
+/// Preprocesses a [Graph] into a [ContractedGraph], contracting nodes in increasing
+/// order of [priority](priority_of) (lazily re-evaluated, as in OSRM's `Contractor`) and
+/// inserting shortcut edges whenever a contracted node was the only shortest path
+/// between two of its remaining neighbors.
+pub fn preprocess(g: &Graph) -> ContractedGraph {
+    let mut out_edges: HashMap<i64, Vec<CHEdge>> = HashMap::new();
+    let mut in_edges: HashMap<i64, Vec<CHEdge>> = HashMap::new();
+    let mut all_edges: HashMap<i64, Vec<CHEdge>> = HashMap::new();
+
+    for node in g.iter() {
+        for edge in g.get_edges(node.id) {
+            let ch_edge = CHEdge {
+                to: edge.to,
+                cost: edge.cost,
+                via: None,
+            };
+            add_edge(&mut out_edges, node.id, ch_edge);
+            add_edge(&mut in_edges, edge.to, CHEdge { to: node.id, ..ch_edge });
+            add_edge(&mut all_edges, node.id, ch_edge);
+        }
+    }
+
+    let mut contracted: HashSet<i64> = HashSet::new();
+    let mut rank: HashMap<i64, u32> = HashMap::new();
+    let mut heap: BinaryHeap<Reverse<(i32, i64)>> = BinaryHeap::new();
+
+    for node in g.iter() {
+        let p = priority_of(&out_edges, &in_edges, &contracted, node.id);
+        heap.push(Reverse((p, node.id)));
+    }
+
+    let mut next_rank = 0u32;
+
+    while let Some(Reverse((_priority, node))) = heap.pop() {
+        if contracted.contains(&node) {
+            continue;
+        }
+
+        // Lazy update: the node's priority may have become stale since it was
+        // pushed, as earlier contractions can add/remove shortcuts touching it.
+        let fresh_priority = priority_of(&out_edges, &in_edges, &contracted, node);
+        if let Some(&Reverse((next_priority, _))) = heap.peek() {
+            if fresh_priority > next_priority {
+                heap.push(Reverse((fresh_priority, node)));
+                continue;
+            }
+        }
+
+        for (u, w, cost) in shortcuts_for(&out_edges, &in_edges, &contracted, node) {
+            let ch_edge = CHEdge { to: w, cost, via: Some(node) };
+            add_edge(&mut out_edges, u, ch_edge);
+            add_edge(&mut in_edges, w, CHEdge { to: u, ..ch_edge });
+            add_edge(&mut all_edges, u, ch_edge);
+        }
+
+        for pred in in_edges.get(&node).cloned().unwrap_or_default() {
+            remove_edge(&mut out_edges, pred.to, node);
+        }
+        for succ in out_edges.get(&node).cloned().unwrap_or_default() {
+            remove_edge(&mut in_edges, succ.to, node);
+        }
+        out_edges.remove(&node);
+        in_edges.remove(&node);
+
+        rank.insert(node, next_rank);
+        next_rank += 1;
+        contracted.insert(node);
+    }
+
+    let mut up: HashMap<i64, Vec<CHEdge>> = HashMap::new();
+    let mut down: HashMap<i64, Vec<CHEdge>> = HashMap::new();
+
+    for (&from, edges) in &all_edges {
+        for &edge in edges {
+            let from_rank = rank.get(&from).copied().unwrap_or(0);
+            let to_rank = rank.get(&edge.to).copied().unwrap_or(0);
+            if to_rank > from_rank {
+                up.entry(from).or_default().push(edge);
+            } else if from_rank > to_rank {
+                down.entry(edge.to).or_default().push(CHEdge { to: from, ..edge });
+            }
+        }
+    }
+
+    ContractedGraph { rank, all_edges, up, down }
+}
+
+/// Unpacks a single edge between `from` and `to` into the full sequence of original
+/// node ids it represents, recursively expanding shortcuts via their middle node.
+fn unpack(all_edges: &HashMap<i64, Vec<CHEdge>>, from: i64, to: i64) -> Vec<i64> {
+    let edge = all_edges[&from]
+        .iter()
+        .find(|e| e.to == to)
+        .expect("unpacking a non-existent edge");
+
+    match edge.via {
+        None => vec![from, to],
+
+        Some(mid) => {
+            let mut path = unpack(all_edges, from, mid);
+            path.pop(); // avoid duplicating `mid`
+            path.extend(unpack(all_edges, mid, to));
+            path
+        }
+    }
+}
+
+/// Uses the [ContractedGraph] built by [preprocess] to find the shortest route between
+/// two nodes with a bidirectional Dijkstra search that only relaxes edges towards
+/// higher-ranked nodes from both ends, meeting somewhere in the middle.
+///
+/// Returns an empty vector if there is no route between the two nodes.
+pub fn find_route(ch: &ContractedGraph, from_id: i64, to_id: i64) -> Result<Vec<i64>, AStarError> {
+    assert_ne!(from_id, 0);
+    assert_ne!(to_id, 0);
+
+    if !ch.rank.contains_key(&from_id) {
+        return Err(AStarError::InvalidReference(from_id));
+    }
+    if !ch.rank.contains_key(&to_id) {
+        return Err(AStarError::InvalidReference(to_id));
+    }
+
+    let (forward_cost, forward_prev) = dijkstra_upward(&ch.up, from_id);
+    let (backward_cost, backward_prev) = dijkstra_upward(&ch.down, to_id);
+
+    let mut best_cost = f32::INFINITY;
+    let mut best_meeting = None;
+
+    for (&node, &cost) in &forward_cost {
+        if let Some(&other_cost) = backward_cost.get(&node) {
+            let total = cost + other_cost;
+            if total < best_cost {
+                best_cost = total;
+                best_meeting = Some(node);
+            }
+        }
+    }
+
+    let Some(meeting) = best_meeting else {
+        return Ok(vec![]);
+    };
+
+    // Walk the forward search's predecessor chain from the meeting node back to
+    // `from_id`; each step's true edge direction is parent -> child, so collect
+    // them and replay in order starting at `from_id`.
+    let mut forward_edges = Vec::new();
+    let mut node = meeting;
+    while let Some(&parent) = forward_prev.get(&node) {
+        forward_edges.push((parent, node));
+        node = parent;
+    }
+    forward_edges.reverse();
+
+    let mut path = vec![from_id];
+    for (parent, child) in forward_edges {
+        let mut segment = unpack(&ch.all_edges, parent, child);
+        segment.remove(0); // already the last node pushed to `path`
+        path.extend(segment);
+    }
+
+    // Walk the backward search's predecessor chain from the meeting node towards
+    // `to_id`; here the true edge direction is child -> parent (the search moves
+    // towards higher rank, away from `to_id`), so segments can be appended directly.
+    let mut node = meeting;
+    while let Some(&parent) = backward_prev.get(&node) {
+        let mut segment = unpack(&ch.all_edges, node, parent);
+        segment.remove(0); // already the last node pushed to `path`
+        path.extend(segment);
+        node = parent;
+    }
+
+    Ok(path)
+}
+
+/// Runs a plain Dijkstra over `adjacency`, from `source`, returning the known costs and
+/// predecessor map once the queue is exhausted.
+fn dijkstra_upward(
+    adjacency: &HashMap<i64, Vec<CHEdge>>,
+    source: i64,
+) -> (HashMap<i64, f32>, HashMap<i64, i64>) {
+    let mut known_costs: HashMap<i64, f32> = HashMap::new();
+    let mut prev: HashMap<i64, i64> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(ordered_float::OrderedCost, i64)>> = BinaryHeap::new();
+
+    known_costs.insert(source, 0.0);
+    queue.push(Reverse((ordered_float::OrderedCost(0.0), source)));
+
+    while let Some(Reverse((ordered_float::OrderedCost(cost), node))) = queue.pop() {
+        if cost > known_costs.get(&node).copied().unwrap_or(f32::INFINITY) {
+            continue;
+        }
+
+        for edge in adjacency.get(&node).map(Vec::as_slice).unwrap_or_default() {
+            let neighbor_cost = cost + edge.cost;
+            if neighbor_cost < known_costs.get(&edge.to).copied().unwrap_or(f32::INFINITY) {
+                known_costs.insert(edge.to, neighbor_cost);
+                prev.insert(edge.to, node);
+                queue.push(Reverse((ordered_float::OrderedCost(neighbor_cost), edge.to)));
+            }
+        }
+    }
+
+    (known_costs, prev)
+}
+
+/// Thin wrapper so `f32` costs can be used as [BinaryHeap] keys (floats don't
+/// implement [Ord] because of `NaN`, which never occurs here as all costs are finite).
+mod ordered_float {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub(super) struct OrderedCost(pub f32);
+
+    impl Eq for OrderedCost {}
+
+    impl PartialOrd for OrderedCost {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    impl Ord for OrderedCost {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.partial_cmp(other).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Graph, Node};
+
+    #[inline]
+    fn simple_graph_fixture() -> Graph {
+        //   200   200   200
+        // 1─────2─────3─────4
+        //       └─────5─────┘
+        //         100    100
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.01, lon: 0.01 },
+                Node { id: 2, osm_id: 2, lat: 0.02, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.03, lon: 0.01 },
+                Node { id: 4, osm_id: 4, lat: 0.04, lon: 0.01 },
+                Node { id: 5, osm_id: 5, lat: 0.03, lon: 0.00 },
+            ],
+            [
+                (1, 2, 200.0),
+                (2, 1, 200.0),
+                (2, 3, 200.0),
+                (2, 5, 100.0),
+                (3, 2, 200.0),
+                (3, 4, 200.0),
+                (4, 3, 200.0),
+                (4, 5, 100.0),
+                (5, 2, 100.0),
+                (5, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn simple() {
+        let g = simple_graph_fixture();
+        let ch = preprocess(&g);
+        assert_eq!(find_route(&ch, 1, 4), Ok(vec![1_i64, 2, 5, 4]));
+    }
+
+    #[test]
+    fn no_route() {
+        let mut g = simple_graph_fixture();
+        g.set_node(Node { id: 6, osm_id: 6, lat: 0.05, lon: 0.05 });
+        let ch = preprocess(&g);
+        assert_eq!(find_route(&ch, 1, 6), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_reference() {
+        let g = simple_graph_fixture();
+        let ch = preprocess(&g);
+        assert_eq!(find_route(&ch, 1, 999), Err(AStarError::InvalidReference(999)));
+    }
+}
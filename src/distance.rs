@@ -26,6 +26,22 @@ pub fn earth_distance(lat1: f32, lon1: f32, lat2: f32, lon2: f32) -> f32 {
     (EARTH_DIAMETER * h.sqrt().asin()) as f32
 }
 
+/// Calculates the initial compass bearing (degrees, `0` = north, increasing clockwise,
+/// wrapped into `[0, 360)`) of the great-circle path from `(lat1, lon1)` to `(lat2, lon2)`.
+pub(crate) fn earth_bearing(lat1: f32, lon1: f32, lat2: f32, lon2: f32) -> f32 {
+    let lat1 = (lat1 as f64).to_radians();
+    let lon1 = (lon1 as f64).to_radians();
+    let lat2 = (lat2 as f64).to_radians();
+    let lon2 = (lon2 as f64).to_radians();
+
+    let dlon = lon2 - lon1;
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+
+    let bearing = y.atan2(x).to_degrees();
+    (bearing as f32).rem_euclid(360.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +61,23 @@ mod tests {
         let d = earth_distance(CENTRUM.0, CENTRUM.1, FALENICA.0, FALENICA.1);
         assert_eq!(d, 15.692483);
     }
+
+    #[test]
+    fn bearing_due_north() {
+        let b = earth_bearing(0.0, 0.0, 1.0, 0.0);
+        assert_eq!(b, 0.0);
+    }
+
+    #[test]
+    fn bearing_due_east() {
+        let b = earth_bearing(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(b, 90.0);
+    }
+
+    #[test]
+    fn bearing_centrum_stadion() {
+        // Stadion is roughly north-east of Centrum.
+        let b = earth_bearing(CENTRUM.0, CENTRUM.1, STADION.0, STADION.1);
+        assert!((0.0..90.0).contains(&b), "expected a north-east bearing, got {b}");
+    }
 }
@@ -3,11 +3,23 @@
 
 use crate::{earth_distance, Edge, Node};
 use std::collections::btree_map::{BTreeMap, Entry};
+use std::collections::{BinaryHeap, HashMap};
+use std::io;
+
+/// Nodes cloned by [GraphChange::clone_node] start at this id (or above the largest id already
+/// in the graph, if that is higher) - see [Node]'s documentation
+/// on why `osm_id >= MAX_NODE_ID` is reserved for turn-restriction (phantom node) processing.
+const MAX_NODE_ID: i64 = 0x0008_0000_0000_0000;
 
 /// Represents an OpenStreetMap network as a set of [Nodes](Node)
 /// and [Edges](Edge) between them.
+///
+/// The second field caches the result of [Graph::compute_components] -
+/// a mapping of node id to its strongly-connected-component id, used to
+/// quickly reject impossible routes. It is not part of the graph's
+/// "public" data and is not recomputed automatically after mutation.
 #[derive(Debug, Default, Clone, PartialEq)]
-pub struct Graph(pub BTreeMap<i64, (Node, Vec<Edge>)>);
+pub struct Graph(pub BTreeMap<i64, (Node, Vec<Edge>)>, Option<HashMap<i64, u32>>);
 
 impl Graph {
     /// Returns the number of nodes in the graph.
@@ -36,9 +48,10 @@ impl Graph {
         N: IntoIterator<Item = Node>,
         E: IntoIterator<Item = (i64, i64, f32)>,
     {
-        let mut g = Graph(BTreeMap::from_iter(
-            nodes.into_iter().map(|n| (n.id, (n, vec![]))),
-        ));
+        let mut g = Graph(
+            BTreeMap::from_iter(nodes.into_iter().map(|n| (n.id, (n, vec![])))),
+            None,
+        );
 
         edges.into_iter().for_each(|(from, to, cost)| {
             g.set_edge(from, Edge { to: to, cost });
@@ -134,6 +147,19 @@ impl Graph {
             .unwrap_or(f32::INFINITY)
     }
 
+    /// Returns a mutable reference to the cost of the edge from `from_id` to `to_id`, letting
+    /// a caller scale/penalize it in place (e.g. a profile discounting `highway=residential`
+    /// after the graph was built) without a delete-then-[Graph::set_edge] round trip. Returns
+    /// `None` if no such edge exists.
+    pub fn get_edge_mut(&mut self, from_id: i64, to_id: i64) -> Option<&mut f32> {
+        self.0
+            .get_mut(&from_id)?
+            .1
+            .iter_mut()
+            .find(|edge| edge.to == to_id)
+            .map(|edge| &mut edge.cost)
+    }
+
     /// Creates or updates an [Edge] from a node with a given id.
     ///
     /// Returns `true` if an existing edge was updated, `false` if a new edge was created.
@@ -183,6 +209,59 @@ impl Graph {
         false
     }
 
+    /// Enumerates every directed path segment from a node with `osm_id == from_osm_id` to a
+    /// node with `osm_id == to_osm_id`, including ones that pass through a single phantom node
+    /// (`id != osm_id`) created by turn-restriction expansion ([GraphBuilder](crate::osm::GraphBuilder)).
+    ///
+    /// After expansion, a single logical junction can be represented by several such segments -
+    /// one per turn variant that survived restriction processing - so there's no longer a single
+    /// edge connecting two OSM nodes. This lets a caller enumerate them, e.g. to check which
+    /// turns a `no_*`/`only_*` restriction suppressed.
+    ///
+    /// Only one intermediate phantom node is considered; restrictions chained through several
+    /// via nodes in a row won't be found by this method.
+    pub fn edges_connecting(&self, from_osm_id: i64, to_osm_id: i64) -> impl Iterator<Item = ConnectingEdge> + '_ {
+        let mut found = Vec::new();
+
+        for (&from, (from_node, edges)) in &self.0 {
+            if from_node.osm_id != from_osm_id {
+                continue;
+            }
+
+            for edge in edges {
+                let Some(mid) = self.get_node(edge.to) else {
+                    continue;
+                };
+
+                if mid.osm_id == to_osm_id {
+                    found.push(ConnectingEdge {
+                        from,
+                        to: edge.to,
+                        via: None,
+                        cost: edge.cost,
+                    });
+                } else if mid.id != mid.osm_id {
+                    // `mid` is a phantom node - look one hop further through it.
+                    for via_edge in self.get_edges(mid.id) {
+                        if self
+                            .get_node(via_edge.to)
+                            .map_or(false, |n| n.osm_id == to_osm_id)
+                        {
+                            found.push(ConnectingEdge {
+                                from,
+                                to: via_edge.to,
+                                via: Some(mid.id),
+                                cost: edge.cost + via_edge.cost,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        found.into_iter()
+    }
+
     /// Replaces all edges from `dst` by cloning all edges outgoing from `src`.
     pub(crate) fn clone_edges(&mut self, dst: i64, src: i64) {
         // Don't clone if dst doesn't exist
@@ -202,4 +281,512 @@ impl Graph {
             *dst_edges = edges;
         }
     }
+
+    /// Computes [strongly connected components](https://en.wikipedia.org/wiki/Strongly_connected_component)
+    /// of the directed graph formed by this graph's nodes and edges, using
+    /// [Tarjan's algorithm](https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm).
+    ///
+    /// The result is cached on the graph and consulted by [find_route](crate::find_route) and
+    /// [find_route_without_turn_around](crate::find_route_without_turn_around) to immediately
+    /// conclude that no route exists between two nodes in different components, instead of
+    /// exhausting the whole search space. The cache is **not** automatically invalidated nor
+    /// recomputed after the graph is mutated - call this method again after any changes.
+    pub fn compute_components(&mut self) {
+        self.1 = Some(tarjan_scc(&self.0));
+    }
+
+    /// Returns the strongly-connected-component id of a node, as computed by
+    /// [Graph::compute_components]. Returns `None` if the component cache hasn't been
+    /// built yet, or if the node doesn't exist.
+    pub fn component_id(&self, node_id: i64) -> Option<u32> {
+        self.1.as_ref().and_then(|components| components.get(&node_id).copied())
+    }
+
+    /// Runs a cost-bounded Dijkstra expansion from `start`, returning every node reachable
+    /// within `max_cost`, keyed by its internal `id` (collapse phantom nodes created by
+    /// turn-restriction expansion to their `osm_id` yourself, if that's what's wanted) and
+    /// paired with the cheapest accumulated cost to reach it.
+    ///
+    /// This is the building block for isochrone/service-area generation: feed the returned
+    /// nodes' coordinates into a convex/concave hull to draw the reachable region. Unlike
+    /// [find_route](crate::find_route), the traversal has no destination to aim for and thus
+    /// no `step_limit` - it bounds itself naturally by never pushing a successor whose
+    /// tentative cost would exceed `max_cost`. Returns an empty map if `start` doesn't exist.
+    pub fn reachable_within(&self, start: i64, max_cost: f32) -> BTreeMap<i64, f32> {
+        let mut costs: BTreeMap<i64, f32> = BTreeMap::new();
+
+        if self.get_node(start).is_none() {
+            return costs;
+        }
+
+        let mut queue: BinaryHeap<ReachableQueueItem> = BinaryHeap::new();
+        costs.insert(start, 0.0);
+        queue.push(ReachableQueueItem { at: start, cost: 0.0 });
+
+        while let Some(item) = queue.pop() {
+            if item.cost > costs.get(&item.at).copied().unwrap_or(f32::INFINITY) {
+                continue;
+            }
+
+            for edge in self.get_edges(item.at) {
+                let next_cost = item.cost + edge.cost;
+                if next_cost > max_cost {
+                    continue;
+                }
+
+                if next_cost < costs.get(&edge.to).copied().unwrap_or(f32::INFINITY) {
+                    costs.insert(edge.to, next_cost);
+                    queue.push(ReachableQueueItem { at: edge.to, cost: next_cost });
+                }
+            }
+        }
+
+        costs
+    }
+
+    /// Writes this graph as a [GraphViz DOT](https://graphviz.org/doc/info/lang.html) digraph:
+    /// one node per [Node], labeled with its id and `osm_id`, and one edge per [Edge], labeled
+    /// with its cost. Phantom nodes created by turn-restriction expansion (those with
+    /// `id != osm_id`) are drawn with a dashed, grey outline, and edges touching them are
+    /// dashed too, so a restricted junction's split (e.g. `no_left_turn`/`only_straight_on`)
+    /// is visible at a glance. See [DotOptions] for further configuration.
+    pub fn to_dot<W: io::Write>(&self, w: &mut W, options: DotOptions) -> io::Result<()> {
+        writeln!(w, "digraph routex {{")?;
+
+        for node in self.iter() {
+            let phantom = node.id != node.osm_id;
+            write!(w, "  {} [label=\"{} (osm {})\"", node.id, node.id, node.osm_id)?;
+            if phantom {
+                write!(w, ", style=dashed, color=grey")?;
+            }
+            if options.include_positions {
+                // GraphViz positions are "x,y[!]" in points; the trailing "!" pins the node
+                // there instead of letting the layout engine move it.
+                write!(w, ", pos=\"{},{}!\"", node.lon, node.lat)?;
+            }
+            writeln!(w, "];")?;
+        }
+
+        for (from_id, (from_node, edges)) in &self.0 {
+            for edge in edges {
+                let phantom = from_node.id != from_node.osm_id
+                    || self.get_node(edge.to).map_or(false, |to| to.id != to.osm_id);
+                write!(w, "  {} -> {} [label=\"{:.1}\"", from_id, edge.to, edge.cost)?;
+                if phantom {
+                    write!(w, ", style=dashed")?;
+                }
+                writeln!(w, "];")?;
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+
+    /// Starts a reversible batch of mutations on this graph - see [GraphChange].
+    pub fn begin_change(&mut self) -> GraphChange<'_> {
+        // Start cloning nodes at MAX_NODE_ID, or the max node ID already in the graph
+        // (in case phantom nodes were already added by GraphBuilder or an earlier GraphChange).
+        let next_node_id = MAX_NODE_ID.max(self.0.keys().next_back().copied().unwrap_or(0) + 1);
+        GraphChange {
+            g: self,
+            next_node_id,
+            created_nodes: Vec::new(),
+            removed_edges: Vec::new(),
+            added_edges: Vec::new(),
+        }
+    }
+}
+
+/// A priority-queue entry used by [Graph::reachable_within]'s Dijkstra expansion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ReachableQueueItem {
+    at: i64,
+    cost: f32,
+}
+
+impl Eq for ReachableQueueItem {}
+
+impl PartialOrd for ReachableQueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        // NOTE: Reversed, as lower costs are "higher" priority and BinaryHeap is a max-heap.
+        other.cost.partial_cmp(&self.cost)
+    }
+}
+
+impl Ord for ReachableQueueItem {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.partial_cmp(self).unwrap()
+    }
+}
+
+/// A single directed path segment yielded by [Graph::edges_connecting].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectingEdge {
+    /// The graph node actually used as the source - may be a phantom node.
+    pub from: i64,
+
+    /// The graph node actually used as the destination - may be a phantom node.
+    pub to: i64,
+
+    /// The phantom "via" node this segment passes through, if it isn't a direct edge.
+    pub via: Option<i64>,
+
+    /// The total cost of this path segment.
+    pub cost: f32,
+}
+
+/// Configuration for [Graph::to_dot].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DotOptions {
+    /// Render each node's original OSM coordinates as a GraphViz `pos` attribute, so the
+    /// output lays out geographically instead of using GraphViz's own layout engine.
+    pub include_positions: bool,
+}
+
+/// A reversible batch of mutations on a [Graph], obtained from [Graph::begin_change].
+///
+/// Every mutation made through this handle ([GraphChange::clone_node], [GraphChange::set_edge],
+/// [GraphChange::remove_edge]) is applied to the underlying [Graph] immediately, while this
+/// handle records enough information - the original cost of a removed edge, the id of a cloned
+/// node - to undo every one of them with a single call to [GraphChange::revert]. This gives
+/// callers a lightweight, versioned/snapshot-style workflow: e.g. clone a node and reroute edges
+/// around it to simulate a turn restriction, route with it in effect, then roll it back exactly,
+/// without rebuilding the whole [Graph] from OSM data.
+pub struct GraphChange<'g> {
+    g: &'g mut Graph,
+    next_node_id: i64,
+    created_nodes: Vec<i64>,
+    removed_edges: Vec<(i64, i64, f32)>,
+    added_edges: Vec<(i64, i64)>,
+}
+
+impl<'g> GraphChange<'g> {
+    /// Returns a read-only view of the [Graph] as currently staged by this handle - i.e.
+    /// including every mutation made through it so far, but before it is dropped or
+    /// [reverted](GraphChange::revert). Useful for running a read-only query (e.g.
+    /// [find_route](crate::find_route)) against the staged graph without giving up the handle.
+    pub fn graph(&self) -> &Graph {
+        self.g
+    }
+
+    /// Clones `src` (including its outgoing edges) under a fresh node id, returning the new id.
+    /// Returns `None` if `src` doesn't exist.
+    pub fn clone_node(&mut self, src: i64) -> Option<i64> {
+        let src_node = self.g.get_node(src)?;
+        let new_id = self.next_node_id;
+        self.next_node_id += 1;
+
+        self.g.set_node(Node { id: new_id, ..src_node });
+        self.g.clone_edges(new_id, src);
+        self.created_nodes.push(new_id);
+        Some(new_id)
+    }
+
+    /// Creates or updates an [Edge], recording it for [GraphChange::revert]: a brand-new edge
+    /// is deleted on revert, while an edge that existed before [Graph::begin_change] gets its
+    /// original cost back.
+    pub fn set_edge(&mut self, from: i64, edge: Edge) -> bool {
+        let old_cost = self.g.get_edge_mut(from, edge.to).map(|cost| *cost);
+        let updated = self.g.set_edge(from, edge);
+        if let Some(cost) = old_cost {
+            // Only the first overwrite of a pre-existing edge holds its original cost.
+            let key = (from, edge.to);
+            if !self.added_edges.contains(&key)
+                && !self.removed_edges.iter().any(|&(f, t, _)| (f, t) == key)
+            {
+                self.removed_edges.push((from, edge.to, cost));
+            }
+        } else if self.g.get_edge_mut(from, edge.to).is_some() {
+            // Graph::set_edge returns false both for a brand-new edge and for an outright
+            // failure (missing from/to node) - distinguish them by checking whether the edge
+            // is actually present now, so a freshly created edge still gets recorded for
+            // deletion on revert().
+            self.added_edges.push((from, edge.to));
+        }
+        updated
+    }
+
+    /// Removes the edge from `from` to `to`, if any, recording its cost for restoration on
+    /// [GraphChange::revert].
+    pub fn remove_edge(&mut self, from: i64, to: i64) -> bool {
+        let cost = self.g.get_edge(from, to);
+        if self.g.delete_edge(from, to) {
+            self.removed_edges.push((from, to, cost));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Undoes every mutation made through this handle, restoring the [Graph] to the state it
+    /// was in right before [Graph::begin_change] was called: deletes edges added via
+    /// [GraphChange::set_edge], restores edges removed via [GraphChange::remove_edge] with
+    /// their original cost, then deletes nodes created via [GraphChange::clone_node].
+    pub fn revert(self) {
+        for (from, to) in self.added_edges {
+            self.g.delete_edge(from, to);
+        }
+        for (from, to, cost) in self.removed_edges {
+            self.g.set_edge(from, Edge { to, cost });
+        }
+        for id in self.created_nodes {
+            self.g.delete_node(id);
+        }
+    }
+}
+
+/// Computes strongly connected components of a directed graph using a single-pass,
+/// iterative version of [Tarjan's algorithm](https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm),
+/// avoiding recursion so it doesn't blow the stack on large graphs.
+fn tarjan_scc(nodes: &BTreeMap<i64, (Node, Vec<Edge>)>) -> HashMap<i64, u32> {
+    struct NodeState {
+        index: u32,
+        lowlink: u32,
+        on_stack: bool,
+    }
+
+    let mut state: HashMap<i64, NodeState> = HashMap::with_capacity(nodes.len());
+    let mut stack: Vec<i64> = Vec::new();
+    let mut components: HashMap<i64, u32> = HashMap::with_capacity(nodes.len());
+    let mut next_index: u32 = 0;
+    let mut next_component: u32 = 0;
+
+    // Explicit work-stack to emulate recursion: (node, next edge index to visit).
+    let mut work: Vec<(i64, usize)> = Vec::new();
+
+    for &root in nodes.keys() {
+        if state.contains_key(&root) {
+            continue;
+        }
+
+        work.push((root, 0));
+
+        while let Some(&(node, edge_idx)) = work.last() {
+            if edge_idx == 0 {
+                // First visit of `node` - assign index/lowlink and push onto the SCC stack.
+                state.insert(
+                    node,
+                    NodeState {
+                        index: next_index,
+                        lowlink: next_index,
+                        on_stack: true,
+                    },
+                );
+                next_index += 1;
+                stack.push(node);
+            }
+
+            let edges = nodes.get(&node).map(|(_, e)| e.as_slice()).unwrap_or(&[]);
+
+            if edge_idx < edges.len() {
+                work.last_mut().unwrap().1 += 1;
+                let successor = edges[edge_idx].to;
+
+                if !state.contains_key(&successor) {
+                    // Successor unvisited - recurse into it.
+                    work.push((successor, 0));
+                } else if state[&successor].on_stack {
+                    let successor_index = state[&successor].index;
+                    let node_state = state.get_mut(&node).unwrap();
+                    node_state.lowlink = node_state.lowlink.min(successor_index);
+                }
+            } else {
+                // All successors visited - this frame is done.
+                work.pop();
+
+                if let Some(&(parent, _)) = work.last() {
+                    let node_lowlink = state[&node].lowlink;
+                    let parent_state = state.get_mut(&parent).unwrap();
+                    parent_state.lowlink = parent_state.lowlink.min(node_lowlink);
+                }
+
+                if state[&node].lowlink == state[&node].index {
+                    // `node` roots a strongly connected component - pop the stack down to it.
+                    loop {
+                        let member = stack.pop().expect("SCC stack must not be empty");
+                        state.get_mut(&member).unwrap().on_stack = false;
+                        components.insert(member, next_component);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    next_component += 1;
+                }
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_components_separate() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0)],
+        );
+
+        g.compute_components();
+
+        assert_eq!(g.component_id(1), g.component_id(2));
+        assert_ne!(g.component_id(1), g.component_id(3));
+    }
+
+    #[test]
+    fn test_compute_components_cycle() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0), (2, 3, 10.0), (3, 1, 10.0)],
+        );
+
+        g.compute_components();
+
+        assert_eq!(g.component_id(1), g.component_id(2));
+        assert_eq!(g.component_id(2), g.component_id(3));
+    }
+
+    #[test]
+    fn test_component_id_uncomputed() {
+        let g = Graph::from_iter(
+            [Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 }],
+            [],
+        );
+
+        assert_eq!(g.component_id(1), None);
+    }
+
+    #[test]
+    fn test_graph_change_revert() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0), (2, 3, 10.0)],
+        );
+
+        let before = g.clone();
+
+        let mut change = g.begin_change();
+        let clone = change.clone_node(2).unwrap();
+        change.remove_edge(1, 2);
+        change.set_edge(1, Edge { to: clone, cost: 10.0 });
+        change.revert();
+
+        assert_eq!(g, before);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 101, osm_id: 2, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0), (1, 101, 10.0)],
+        );
+
+        let mut out = Vec::new();
+        g.to_dot(&mut out, DotOptions::default()).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+
+        assert!(dot.starts_with("digraph routex {"));
+        assert!(dot.contains("1 -> 2"));
+        assert!(dot.contains("1 -> 101"));
+        assert!(dot.contains("101 [label=\"101 (osm 2)\", style=dashed, color=grey];"));
+        assert!(!dot.contains("1 [label=\"1 (osm 1)\", style=dashed"));
+    }
+
+    #[test]
+    fn test_get_edge_mut() {
+        let mut g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0)],
+        );
+
+        *g.get_edge_mut(1, 2).unwrap() *= 2.0;
+        assert_eq!(g.get_edge(1, 2), 20.0);
+
+        assert!(g.get_edge_mut(1, 3).is_none());
+    }
+
+    #[test]
+    fn test_edges_connecting() {
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 101, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0), (1, 101, 10.0), (101, 3, 5.0)],
+        );
+
+        // Direct connections to both the canonical and the phantom node with osm_id == 2.
+        let mut to_2: Vec<_> = g.edges_connecting(1, 2).collect();
+        to_2.sort_by_key(|e| e.to);
+        assert_eq!(
+            to_2,
+            vec![
+                ConnectingEdge { from: 1, to: 2, via: None, cost: 10.0 },
+                ConnectingEdge { from: 1, to: 101, via: None, cost: 10.0 },
+            ]
+        );
+
+        // Restriction -2 -> -3 is unavailable from the canonical node 2, but the phantom
+        // node 101 routes through to 3.
+        let to_3: Vec<_> = g.edges_connecting(1, 3).collect();
+        assert_eq!(
+            to_3,
+            vec![ConnectingEdge { from: 1, to: 3, via: Some(101), cost: 15.0 }]
+        );
+
+        assert!(g.edges_connecting(3, 1).next().is_none());
+    }
+
+    #[test]
+    fn test_reachable_within() {
+        //      10    10    10
+        //  1 ─────2─────3─────4
+        //          \
+        //           \100
+        //            5
+
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 0.0 },
+                Node { id: 4, osm_id: 4, lat: 0.0, lon: 0.0 },
+                Node { id: 5, osm_id: 5, lat: 0.0, lon: 0.0 },
+            ],
+            [(1, 2, 10.0), (2, 3, 10.0), (3, 4, 10.0), (2, 5, 100.0)],
+        );
+
+        let reachable = g.reachable_within(1, 25.0);
+        assert_eq!(
+            reachable,
+            BTreeMap::from([(1, 0.0), (2, 10.0), (3, 20.0)]),
+        );
+
+        // An unknown starting node yields no reachable nodes.
+        assert!(g.reachable_within(404, 100.0).is_empty());
+    }
 }
@@ -1,7 +1,10 @@
 // (c) Copyright 2025 Mikołaj Kuranowski
 // SPDX-License-Identifier: MIT
 
-use crate::{earth_distance, Node};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{earth_distance, Graph, Node};
 
 /// KDTree implements the [k-d tree data structure](https://en.wikipedia.org/wiki/K-d_tree),
 /// which can be used to speed up nearest-neighbor search for large datasets. Practice shows
@@ -23,19 +26,47 @@ pub struct KDTree {
 impl KDTree {
     /// Finds the closest canonical (`id == osm_id`) [Node] to the given position.
     pub fn find_nearest_node(&self, lat: f32, lon: f32) -> Node {
-        self.find_nearest_node_impl(lat, lon, false).0
+        self.find_nearest_nodes(lat, lon, 1)
+            .into_iter()
+            .next()
+            .expect("KDTree is never empty")
     }
 
-    fn find_nearest_node_impl(&self, lat: f32, lon: f32, lon_divides: bool) -> (Node, f32) {
-        // Start by assuming that pivot is the closest
-        let mut best = self.pivot;
-        let mut best_dist = earth_distance(lat, lon, best.lat, best.lon);
+    /// Finds up to `k` closest canonical (`id == osm_id`) [Nodes](Node) to the given position,
+    /// sorted by ascending distance. Useful for map-matching/candidate-snapping, where a caller
+    /// wants to consider several nearby nodes rather than committing to the single closest one.
+    /// Returns fewer than `k` nodes if the tree doesn't have that many.
+    pub fn find_nearest_nodes(&self, lat: f32, lon: f32, k: usize) -> Vec<Node> {
+        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.find_nearest_nodes_impl(lat, lon, k, false, &mut heap);
+        }
+
+        let mut found = heap.into_vec();
+        found.sort_by(|a, b| a.dist.partial_cmp(&b.dist).unwrap());
+        found.into_iter().map(|e| e.node).collect()
+    }
+
+    fn find_nearest_nodes_impl(
+        &self,
+        lat: f32,
+        lon: f32,
+        k: usize,
+        lon_divides: bool,
+        heap: &mut BinaryHeap<HeapEntry>,
+    ) {
+        // Push the pivot as a candidate, evicting the current worst once the heap overflows k.
+        let dist = earth_distance(lat, lon, self.pivot.lat, self.pivot.lon);
+        heap.push(HeapEntry { dist, node: self.pivot });
+        if heap.len() > k {
+            heap.pop();
+        }
 
         // Select which branch to recurse into first
         let first_left = if lon_divides {
-            lon < best.lon
+            lon < self.pivot.lon
         } else {
-            lat < best.lat
+            lat < self.pivot.lat
         };
         let (first, second) = if first_left {
             (&self.left, &self.right)
@@ -43,19 +74,15 @@ impl KDTree {
             (&self.right, &self.left)
         };
 
-        // Recurse into the first branch
+        // Always recurse into the first (near) branch
         if let Some(ref branch) = first {
-            let (alt, alt_dist) = branch.find_nearest_node_impl(lat, lon, !lon_divides);
-            if alt_dist < best_dist {
-                best = alt;
-                best_dist = alt_dist;
-            }
+            branch.find_nearest_nodes_impl(lat, lon, k, !lon_divides, heap);
         }
 
         // (Optionally) recurse into the second branch
         if let Some(ref branch) = second {
-            // A closer node is possible in the second branch if and only if
-            // the splitting axis is closer than the current best candidate.
+            // A closer node is possible in the second branch if the heap isn't full yet, or if
+            // the splitting axis is closer than the current worst candidate in the heap.
             let (axis_lat, axis_lon) = if lon_divides {
                 (lat, self.pivot.lon)
             } else {
@@ -63,16 +90,108 @@ impl KDTree {
             };
             let dist_to_axis = earth_distance(lat, lon, axis_lat, axis_lon);
 
-            if dist_to_axis < best_dist {
-                let (alt, alt_dist) = branch.find_nearest_node_impl(lat, lon, !lon_divides);
-                if alt_dist < best_dist {
-                    best = alt;
-                    best_dist = alt_dist;
-                }
+            if heap.len() < k || dist_to_axis < heap.peek().unwrap().dist {
+                branch.find_nearest_nodes_impl(lat, lon, k, !lon_divides, heap);
             }
         }
+    }
 
-        return (best, best_dist);
+    /// Finds every canonical node inside the `[min_lat, min_lon, max_lat, max_lon]`
+    /// rectangle. Useful for extracting a region of the graph (e.g. to build a smaller
+    /// sub-graph) without scanning every node.
+    pub fn find_nodes_within_bbox(&self, bbox: [f32; 4]) -> Vec<Node> {
+        let mut found = Vec::new();
+        self.find_nodes_within_bbox_impl(bbox, false, &mut found);
+        found
+    }
+
+    fn find_nodes_within_bbox_impl(&self, bbox: [f32; 4], lon_divides: bool, found: &mut Vec<Node>) {
+        let [min_lat, min_lon, max_lat, max_lon] = bbox;
+
+        if self.pivot.lat >= min_lat
+            && self.pivot.lat <= max_lat
+            && self.pivot.lon >= min_lon
+            && self.pivot.lon <= max_lon
+        {
+            found.push(self.pivot);
+        }
+
+        // The left subtree only holds nodes whose split coordinate is <= the pivot's, and
+        // the right subtree only holds nodes whose split coordinate is >= the pivot's - so
+        // a subtree can be skipped entirely once the query range can no longer overlap it.
+        let (split, min_bound, max_bound) = if lon_divides {
+            (self.pivot.lon, min_lon, max_lon)
+        } else {
+            (self.pivot.lat, min_lat, max_lat)
+        };
+
+        if let Some(ref left) = self.left {
+            if min_bound <= split {
+                left.find_nodes_within_bbox_impl(bbox, !lon_divides, found);
+            }
+        }
+
+        if let Some(ref right) = self.right {
+            if max_bound >= split {
+                right.find_nodes_within_bbox_impl(bbox, !lon_divides, found);
+            }
+        }
+    }
+
+    /// Finds every canonical node within `radius_km` of the given position, using
+    /// [earth_distance] to filter candidates returned by the tree. The result is not sorted.
+    pub fn find_within_radius(&self, lat: f32, lon: f32, radius_km: f32) -> Vec<Node> {
+        let mut found = Vec::new();
+        self.find_within_radius_impl(lat, lon, radius_km, false, &mut found);
+        found
+    }
+
+    fn find_within_radius_impl(
+        &self,
+        lat: f32,
+        lon: f32,
+        radius_km: f32,
+        lon_divides: bool,
+        found: &mut Vec<Node>,
+    ) {
+        if earth_distance(lat, lon, self.pivot.lat, self.pivot.lon) <= radius_km {
+            found.push(self.pivot);
+        }
+
+        // Unlike nearest-neighbor search, both branches must be visited whenever the
+        // splitting plane is within `radius_km`, as either side might still contain
+        // points inside the radius.
+        let axis_distance = if lon_divides {
+            earth_distance(lat, lon, lat, self.pivot.lon)
+        } else {
+            earth_distance(lat, lon, self.pivot.lat, lon)
+        };
+
+        if let Some(ref left) = self.left {
+            if axis_distance <= radius_km || self.is_left_of(lat, lon, lon_divides) {
+                left.find_within_radius_impl(lat, lon, radius_km, !lon_divides, found);
+            }
+        }
+
+        if let Some(ref right) = self.right {
+            if axis_distance <= radius_km || !self.is_left_of(lat, lon, lon_divides) {
+                right.find_within_radius_impl(lat, lon, radius_km, !lon_divides, found);
+            }
+        }
+    }
+
+    fn is_left_of(&self, lat: f32, lon: f32, lon_divides: bool) -> bool {
+        if lon_divides {
+            lon < self.pivot.lon
+        } else {
+            lat < self.pivot.lat
+        }
+    }
+
+    /// Builds a k-d tree directly from a [Graph]'s nodes.
+    /// Non-canonical (`id != osm_id`) nodes are skipped when building the tree.
+    pub fn build_from_graph(g: &Graph) -> Option<Self> {
+        Self::from_iter(g.iter().copied())
     }
 
     /// Builds a k-d tree from an iterable of [Nodes](Node).
@@ -128,6 +247,29 @@ fn box_option<T>(o: Option<T>) -> Option<Box<T>> {
     o.map(|thing| Box::new(thing))
 }
 
+/// A candidate [Node] keyed by its distance to the query point, ordered so that
+/// [BinaryHeap] (a max-heap) keeps the *worst* (farthest) candidate at the root - letting
+/// [KDTree::find_nearest_nodes_impl] evict it once more than `k` candidates have been seen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HeapEntry {
+    dist: f32,
+    node: Node,
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +339,92 @@ mod tests {
         assert_eq!(tree.find_nearest_node(0.05, 0.08).id, 5);
         assert_eq!(tree.find_nearest_node(0.09, 0.06).id, 8);
     }
+
+    #[test]
+    fn find_nearest_nodes() {
+        let tree = KDTree::build(&mut [
+            Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+            Node { id: 2, osm_id: 2, lat: 0.00, lon: 0.01 },
+            Node { id: 3, osm_id: 3, lat: 0.00, lon: 0.02 },
+            Node { id: 4, osm_id: 4, lat: 0.00, lon: 10.0 },
+        ])
+        .expect("k-d tree from non-empty slice must not be empty");
+
+        // Sorted by ascending distance from the query point.
+        assert_eq!(
+            tree.find_nearest_nodes(0.0, 0.0, 3)
+                .into_iter()
+                .map(|n| n.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // k=1 matches find_nearest_node.
+        assert_eq!(tree.find_nearest_nodes(0.0, 0.0, 1), vec![tree.find_nearest_node(0.0, 0.0)]);
+
+        // Asking for more nodes than exist just returns every node, still sorted.
+        assert_eq!(
+            tree.find_nearest_nodes(0.0, 0.0, 10)
+                .into_iter()
+                .map(|n| n.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+
+        // k=0 returns nothing.
+        assert!(tree.find_nearest_nodes(0.0, 0.0, 0).is_empty());
+    }
+
+    #[test]
+    fn find_within_radius() {
+        let tree = KDTree::build(&mut [
+            Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+            Node { id: 2, osm_id: 2, lat: 0.00, lon: 0.01 },
+            Node { id: 3, osm_id: 3, lat: 0.00, lon: 10.0 },
+        ])
+        .expect("k-d tree from non-empty slice must not be empty");
+
+        let mut found: Vec<i64> = tree
+            .find_within_radius(0.0, 0.0, 5.0)
+            .into_iter()
+            .map(|n| n.id)
+            .collect();
+        found.sort();
+
+        assert_eq!(found, vec![1, 2]);
+    }
+
+    #[test]
+    fn find_nodes_within_bbox() {
+        let tree = KDTree::build(&mut [
+            Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+            Node { id: 2, osm_id: 2, lat: 0.00, lon: 0.01 },
+            Node { id: 3, osm_id: 3, lat: 0.00, lon: 10.0 },
+            Node { id: 4, osm_id: 4, lat: 10.0, lon: 0.00 },
+        ])
+        .expect("k-d tree from non-empty slice must not be empty");
+
+        let mut found: Vec<i64> = tree
+            .find_nodes_within_bbox([-1.0, -1.0, 1.0, 1.0])
+            .into_iter()
+            .map(|n| n.id)
+            .collect();
+        found.sort();
+
+        assert_eq!(found, vec![1, 2]);
+    }
+
+    #[test]
+    fn build_from_graph() {
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+                Node { id: 2, osm_id: 2, lat: 1.00, lon: 1.00 },
+            ],
+            [],
+        );
+
+        let tree = KDTree::build_from_graph(&g).expect("non-empty graph");
+        assert_eq!(tree.find_nearest_node(0.1, 0.1).id, 1);
+    }
 }
@@ -16,6 +16,7 @@
 //!     profile: &routex::osm::CAR_PROFILE,
 //!     file_format: routex::osm::FileFormat::Unknown,
 //!     bbox: [0.0; 4],
+//!     include_metadata: false,
 //! };
 //! routex::osm::add_features_from_file(
 //!     &mut g,
@@ -25,22 +26,39 @@
 //!
 //! let start_node = g.find_nearest_node(43.7384, 7.4246).unwrap();
 //! let end_node = g.find_nearest_node(43.7478, 7.4323).unwrap();
-//! let route = routex::find_route_without_turn_around(&g, start_node.id, end_node.id, routex::DEFAULT_STEP_LIMIT)
-//!     .expect("failed to find route");
+//! let route = routex::find_route_without_turn_around(
+//!     &g,
+//!     start_node.id,
+//!     end_node.id,
+//!     routex::DEFAULT_STEP_LIMIT,
+//!     None,
+//! )
+//! .expect("failed to find route");
 //!
 //! println!("Route: {:?}", route);
 //! ```
 
 mod astar;
+pub mod batch;
 pub mod c;
+pub mod ch;
 mod distance;
 mod graph;
 mod kd;
+pub mod matrix;
 pub mod osm;
+pub mod polyline;
+pub mod simplify;
+pub mod spatial;
+pub mod trip;
 
-pub use astar::{find_route, find_route_without_turn_around, AStarError, DEFAULT_STEP_LIMIT};
+pub use astar::{
+    find_route, find_route_beam, find_route_between_coords, find_route_via,
+    find_route_without_turn_around, k_shortest_paths, AStarError, ProgressCallback,
+    DEFAULT_STEP_LIMIT, MAX_VIA_WAYPOINTS, PROGRESS_CALLBACK_INTERVAL,
+};
 pub use distance::earth_distance;
-pub use graph::Graph;
+pub use graph::{ConnectingEdge, DotOptions, Graph, GraphChange};
 pub use kd::KDTree;
 
 /// Represents an element of the [Graph].
@@ -39,8 +39,13 @@ pub fn main() -> Result<(), Box<dyn Error>> {
         .find_nearest_node(cli.end_lat, cli.end_lon)
         .expect("no node corresponding to the given end position");
 
-    let route =
-        routex::find_route_without_turn_around(&g, start.id, end.id, routex::DEFAULT_STEP_LIMIT)?;
+    let route = routex::find_route_without_turn_around(
+        &g,
+        start.id,
+        end.id,
+        routex::DEFAULT_STEP_LIMIT,
+        None,
+    )?;
 
     println!("{{");
     println!("  \"type\": \"FeatureCollection\",");
@@ -77,6 +82,7 @@ fn load_graph<P: AsRef<Path>>(path: P) -> Result<routex::Graph, GraphLoadError>
         profile: &routex::osm::CAR_PROFILE,
         file_format: routex::osm::FileFormat::Xml,
         bbox: [0.0; 4],
+        include_metadata: false,
     };
     match routex::osm::add_features_from_file(&mut g, &options, path.as_ref()) {
         Ok(()) => Ok(g),
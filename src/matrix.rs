@@ -0,0 +1,148 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! Many-to-many cost matrices ("table" queries), as used by routing clients that need
+//! every pairwise cost between a set of sources and targets at once, rather than
+//! issuing `N*M` independent [find_route](crate::find_route) calls.
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use rayon::prelude::*;
+
+use crate::{Edge, Graph};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QueueItem {
+    at: i64,
+    cost: f32,
+}
+
+impl Eq for QueueItem {}
+
+impl PartialOrd for QueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        // NOTE: Reversed, as lower costs are "higher" priority and BinaryHeap is a max-heap.
+        other.cost.partial_cmp(&self.cost)
+    }
+}
+
+impl Ord for QueueItem {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.partial_cmp(self).unwrap()
+    }
+}
+
+/// Runs a single-source Dijkstra from `source`, stopping as soon as every node in
+/// `targets` has been settled (rather than exhausting the whole graph), up to
+/// `step_limit` expansions. Returns the cost to each target, in the same order as
+/// `targets`, with unreached targets (including those cut off by `step_limit`)
+/// represented as [f32::INFINITY].
+fn one_to_many(g: &Graph, source: i64, targets: &[i64], step_limit: usize) -> Vec<f32> {
+    let mut known_costs: HashMap<i64, f32> = HashMap::new();
+    let mut queue: BinaryHeap<QueueItem> = BinaryHeap::new();
+    let mut remaining: HashSet<i64> = targets.iter().copied().collect();
+    let mut steps: usize = 0;
+
+    known_costs.insert(source, 0.0);
+    queue.push(QueueItem { at: source, cost: 0.0 });
+    remaining.remove(&source);
+
+    while let Some(item) = queue.pop() {
+        if item.cost > known_costs.get(&item.at).copied().unwrap_or(f32::INFINITY) {
+            continue;
+        }
+
+        remaining.remove(&item.at);
+        if remaining.is_empty() {
+            break;
+        }
+
+        steps += 1;
+        if steps > step_limit {
+            break;
+        }
+
+        for &Edge { to, cost: edge_cost } in g.get_edges(item.at) {
+            if g.get_node(to).is_none() {
+                continue;
+            }
+
+            let neighbor_cost = item.cost + edge_cost;
+            if neighbor_cost < known_costs.get(&to).copied().unwrap_or(f32::INFINITY) {
+                known_costs.insert(to, neighbor_cost);
+                queue.push(QueueItem { at: to, cost: neighbor_cost });
+            }
+        }
+    }
+
+    targets
+        .iter()
+        .map(|to| known_costs.get(to).copied().unwrap_or(f32::INFINITY))
+        .collect()
+}
+
+/// Computes an `N*M` matrix of costs between `sources` and `targets`, running one
+/// many-target Dijkstra per source (in parallel, via `rayon`) rather than `N*M`
+/// independent searches. Unreachable pairs are represented as [f32::INFINITY].
+///
+/// `row[i][j]` is the cost from `sources[i]` to `targets[j]`.
+///
+/// A free function rather than a `Graph::distance_matrix` method, for the same reason
+/// [find_route](crate::find_route) isn't a `Graph` method either - it only needs read
+/// access to the graph, and `rayon`'s `par_iter` below borrows it across threads.
+pub fn table(g: &Graph, sources: &[i64], targets: &[i64], step_limit: usize) -> Vec<Vec<f32>> {
+    sources
+        .par_iter()
+        .map(|&source| one_to_many(g, source, targets, step_limit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn simple_graph_fixture() -> Graph {
+        //   200   200   200
+        // 1─────2─────3─────4
+        //       └─────5─────┘
+        //         100    100
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.01, lon: 0.01 },
+                Node { id: 2, osm_id: 2, lat: 0.02, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.03, lon: 0.01 },
+                Node { id: 4, osm_id: 4, lat: 0.04, lon: 0.01 },
+                Node { id: 5, osm_id: 5, lat: 0.03, lon: 0.00 },
+            ],
+            [
+                (1, 2, 200.0),
+                (2, 1, 200.0),
+                (2, 3, 200.0),
+                (2, 5, 100.0),
+                (3, 2, 200.0),
+                (3, 4, 200.0),
+                (4, 3, 200.0),
+                (4, 5, 100.0),
+                (5, 2, 100.0),
+                (5, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn table_costs() {
+        let g = simple_graph_fixture();
+        let m = table(&g, &[1, 4], &[1, 4], 100);
+        assert_eq!(m, vec![vec![0.0, 300.0], vec![300.0, 0.0]]);
+    }
+
+    #[test]
+    fn unreachable_pair() {
+        let mut g = simple_graph_fixture();
+        g.set_node(Node { id: 6, osm_id: 6, lat: 0.05, lon: 0.05 });
+        let m = table(&g, &[1], &[6], 100);
+        assert_eq!(m, vec![vec![f32::INFINITY]]);
+    }
+}
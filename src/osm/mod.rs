@@ -5,11 +5,13 @@ mod profile;
 mod reader;
 
 pub use profile::{
-    Penalty, Profile, BICYCLE_PROFILE, BUS_PROFILE, CAR_PROFILE, FOOT_PROFILE, RAILWAY_PROFILE,
-    SUBWAY_PROFILE, TRAM_PROFILE,
+    Access, Barrier, Condition, Month, OpeningHoursInstant, Penalty, Profile, Rule, Speed,
+    Weekday, WeightMode, BICYCLE_PROFILE, BUS_PROFILE, CAR_PROFILE, FOOT_PROFILE,
+    RAILWAY_PROFILE, SUBWAY_PROFILE, TRAM_PROFILE,
 };
 pub use reader::{
-    add_features_from_buffer, add_features_from_file, add_features_from_io, FileFormat, Options,
+    add_features_from_buffer, add_features_from_file, add_features_from_io, FileFormat,
+    GraphBuilder, Options, TryAddFeaturesError,
 };
 
 #[cfg(test)]
@@ -129,6 +131,7 @@ mod tests {
                 profile: &CAR_PROFILE,
                 file_format: FileFormat::Xml,
                 bbox: [0.0; 4],
+                include_metadata: false,
             };
             add_features_from_buffer(&mut g, &options, DATA).unwrap();
             g
@@ -147,6 +150,7 @@ mod tests {
                 profile: &CAR_PROFILE,
                 file_format: FileFormat::XmlGz,
                 bbox: [0.0; 4],
+                include_metadata: false,
             };
             add_features_from_buffer(&mut g, &options, DATA).unwrap();
             g
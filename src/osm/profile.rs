@@ -3,6 +3,21 @@
 
 use std::collections::HashMap;
 
+/// Speed (km/h) assumed by [Profile::edge_cost] when a way has neither a valid
+/// `maxspeed` tag nor a matching [Profile::speed_profile] entry.
+const DEFAULT_SPEED_KMH: f32 = 30.0;
+
+/// Conversion factor from miles per hour to km/h, for `maxspeed=* mph` values.
+const KMH_PER_MPH: f32 = 1.609344;
+
+/// Conversion factor from knots to km/h, for `maxspeed=* knots` values.
+const KMH_PER_KNOT: f32 = 1.852;
+
+/// Absolute turn angle (degrees) above which [Profile::turn_cost] treats a turn as a
+/// u-turn, applying the full [Profile::u_turn_penalty] instead of a [Profile::turn_penalty]
+/// scaled by sharpness.
+const U_TURN_ANGLE_DEG: f32 = 150.0;
+
 /// Describes how to convert OSM data into a [Graph](crate::Graph).
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Profile<'a> {
@@ -41,19 +56,127 @@ pub struct Profile<'a> {
     ///   match any [Penalty].
     pub penalties: &'a [Penalty<'a>],
 
-    /// Array of OSM [access tags](https://wiki.openstreetmap.org/wiki/Key:access#Land-based_transportation)
-    /// (in order from least to most specific) to consider when checking for road prohibitions.
+    /// Array of secondary multiplicative [Penalty] factors, e.g. for
+    /// [surface](https://wiki.openstreetmap.org/wiki/Key:surface) or
+    /// [smoothness](https://wiki.openstreetmap.org/wiki/Key:smoothness).
+    ///
+    /// Unlike [Profile::penalties], every matching factor (not just the first) is applied:
+    /// [Profile::way_penalty] multiplies the first matching base [Penalty] by the product
+    /// of every matching entry here. A `highway=track` base penalty of `2.0` combined with
+    /// a `surface=mud` factor of `8.0` yields a cost multiplier of `16.0`.
+    pub factors: &'a [Penalty<'a>],
+
+    /// Array of [Rule]s, each an arbitrary conjunction of [Condition]s, extending
+    /// [Profile::penalties] for selectors an exact key/value match can't express -
+    /// regex value alternations, key presence/absence, or a conjunction across several
+    /// keys (e.g. `[junction][!highway]`). Consulted by [Profile::way_penalty] only when
+    /// no [Profile::penalties] entry matches; the first firing rule (in order) wins, same
+    /// as [Profile::penalties]. A plain `Penalty { key, value, penalty }` is equivalent to
+    /// `Rule { conditions: &[Condition::Equals(key, value)], penalty }`.
+    pub penalty_rules: &'a [Rule<'a>],
+
+    /// Array of [Rule]s extending [Profile::factors] the same way [Profile::penalty_rules]
+    /// extends [Profile::penalties]. Unlike [Profile::penalty_rules], every firing rule
+    /// (not just the first) is applied: [Profile::way_penalty] multiplies in the product
+    /// of every matching entry here, on top of [Profile::factors].
+    pub factor_rules: &'a [Rule<'a>],
+
+    /// Array of [barriers](Barrier) which a node can carry to block or penalize routing
+    /// through it, e.g. `barrier=gate` or `barrier=bollard`.
     ///
-    /// This array is used mainly used to follow the access tags, but also to follow mode-specific
+    /// A node is matched against all [Barrier] objects in order, and once an exact key
+    /// and value match is found, the resulting multiplier is applied by
+    /// [Profile::node_penalty]. Absent any match (including a node without any matching
+    /// tag at all), the node does not affect routing.
+    pub barriers: &'a [Barrier<'a>],
+
+    /// Selects what [Profile::edge_cost] optimizes for. Defaults to [WeightMode::Distance].
+    pub weight_mode: WeightMode,
+
+    /// Array of default speeds, consulted by [Profile::edge_cost] under
+    /// [WeightMode::Duration]/[WeightMode::Routability]; see [Speed].
+    pub speed_profile: &'a [Speed<'a>],
+
+    /// The profile's OSM [access tag](https://wiki.openstreetmap.org/wiki/Key:access#Land-based_transportation)
+    /// hierarchy, declared from least to most specific, e.g. `["access", "vehicle",
+    /// "motor_vehicle", "motorcar"]` - `motorcar` overrides `motor_vehicle` overrides
+    /// `vehicle` overrides plain `access`, regardless of which one is actually present on a
+    /// given way. [Profile::access_level] (and [Profile::node_penalty]) resolve the effective
+    /// value by walking this chain from the most specific end and taking the first key that's
+    /// present; [Profile::is_exempted] instead checks every mode in it against `except`.
+    ///
+    /// This array is used mainly to follow the access tags, but also to follow mode-specific
     /// one-way and turn restrictions (see [Profile::is_allowed], [Profile::way_direction] and
     /// [Profile::is_exempted]).
     pub access: &'a [&'a str],
 
+    /// Access tag values (checked against the most specific mode in [Profile::access])
+    /// that fully block routing, e.g. `"no"`, `"private"` or a mode reserved for other
+    /// traffic like `"agricultural"`/`"forestry"`. Checked by [Profile::access_level]
+    /// before [Profile::access_restricted_values].
+    pub access_disallowed_values: &'a [&'a str],
+
+    /// Access tag values (checked against the most specific mode in [Profile::access])
+    /// that still allow routing but discourage through-traffic, e.g. `"destination"` or
+    /// `"customers"`. [Profile::way_penalty] multiplies by
+    /// [Profile::restricted_access_penalty] instead of returning [f32::INFINITY], so a
+    /// route can still start or end on such a way without ever passing through it. Any
+    /// value absent from both this and [Profile::access_disallowed_values] - e.g. `"yes"`,
+    /// `"designated"` or `"permissive"` - is simply [Access::Allowed].
+    pub access_restricted_values: &'a [&'a str],
+
+    /// Multiplier applied by [Profile::way_penalty] to ways whose [Profile::access_level]
+    /// is [Access::Restricted].
+    pub restricted_access_penalty: f32,
+
+    /// Vehicle height (m). When set, [Profile::is_allowed] rejects ways with a lower
+    /// `maxheight` tag.
+    pub vehicle_height: Option<f32>,
+
+    /// Vehicle width (m). When set, [Profile::is_allowed] rejects ways with a lower
+    /// `maxwidth` tag.
+    pub vehicle_width: Option<f32>,
+
+    /// Vehicle length (m). When set, [Profile::is_allowed] rejects ways with a lower
+    /// `maxlength` tag.
+    pub vehicle_length: Option<f32>,
+
+    /// Vehicle weight (t). When set, [Profile::is_allowed] rejects ways with a lower
+    /// `maxweight` tag, or tagged `hgv=no`.
+    pub vehicle_weight: Option<f32>,
+
     /// Force no routing over [motorroad=yes](https://wiki.openstreetmap.org/wiki/Key:motorroad) ways.
     pub disallow_motorroad: bool,
 
     /// Force ignoring of [turn restrictions](https://wiki.openstreetmap.org/wiki/Turn_restriction).
     pub disable_restrictions: bool,
+
+    /// Additive cost of a u-turn (an [Profile::turn_cost] angle above [U_TURN_ANGLE_DEG]),
+    /// in the same units as [Profile::edge_cost].
+    pub u_turn_penalty: f32,
+
+    /// Additive cost of turning at a junction, scaled by how sharp the turn is (and by
+    /// [Profile::turn_bias] for a left turn), in the same units as [Profile::edge_cost].
+    /// Consulted by [Profile::turn_cost].
+    pub turn_penalty: f32,
+
+    /// Multiplier applied to [Profile::turn_penalty] for a left turn, expressing a
+    /// preference for right turns (`turn_bias > 1.0`) - customary for right-hand-traffic
+    /// countries, where a left turn crosses oncoming traffic. `1.0` treats both turn
+    /// directions equally.
+    pub turn_bias: f32,
+
+    /// Additive cost of passing through a node tagged `highway=traffic_signals` or
+    /// `highway=stop`, in the same units as [Profile::edge_cost]. Consulted by
+    /// [Profile::turn_cost].
+    pub traffic_signal_penalty: f32,
+
+    /// The point in time [Profile::is_allowed], [Profile::way_direction],
+    /// [Profile::restriction_kind] and [Profile::is_exempted] evaluate `*:conditional`
+    /// tags against (see [Profile::effective_value]). `None` (the default) ignores every
+    /// `:conditional` tag, using only the unconditional value, same as before these
+    /// existed.
+    pub query_time: Option<OpeningHoursInstant>,
 }
 
 /// Numeric multiplier for OSM ways with specific keys and values.
@@ -72,6 +195,293 @@ pub struct Penalty<'a> {
     pub penalty: f32,
 }
 
+/// A single MapCSS-style match condition against one OSM tag, ANDed together with its
+/// siblings in [Rule::conditions] to select matching ways - modeled after JOSM validator
+/// selectors like `[junction][!highway]` or `[surface=~/^(unpaved|gravel)$/]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition<'a> {
+    /// `[key]` - the tag is present, regardless of value.
+    KeyPresent(&'a str),
+
+    /// `[!key]` - the tag is absent.
+    KeyAbsent(&'a str),
+
+    /// `[key=value]` - the tag is present and equals `value` exactly.
+    Equals(&'a str, &'a str),
+
+    /// `[key!=value]` - the tag is present and differs from `value`. An absent tag does
+    /// not match, same as JOSM's `!=` (use [Condition::KeyAbsent] to also allow that).
+    NotEquals(&'a str, &'a str),
+
+    /// `[key=~/pattern/]` - the tag is present and matches `pattern` under
+    /// [match_value_pattern], e.g. `Regex("surface", "^(unpaved|gravel|dirt)$")`.
+    ///
+    /// `pattern` is interpreted on every call rather than pre-compiled - only a small
+    /// regex subset is supported (see [match_value_pattern]), cheap enough to parse
+    /// alongside the rest of a way's tags, and simple enough to stay a `const`-friendly
+    /// `&'a str` like every other [Profile] field.
+    Regex(&'a str, &'a str),
+}
+
+impl Condition<'_> {
+    /// Checks this single condition against `tags`.
+    fn matches(&self, tags: &HashMap<String, String>) -> bool {
+        match *self {
+            Condition::KeyPresent(key) => tags.contains_key(key),
+            Condition::KeyAbsent(key) => !tags.contains_key(key),
+            Condition::Equals(key, value) => tags.get(key).map(|v| v.as_str()) == Some(value),
+            Condition::NotEquals(key, value) => tags.get(key).map_or(false, |v| v != value),
+            Condition::Regex(key, pattern) => {
+                tags.get(key).map_or(false, |v| match_value_pattern(pattern, v))
+            }
+        }
+    }
+}
+
+/// Matches `value` against the small regex subset supported by [Condition::Regex]: an
+/// optional leading `^` anchor, an optional trailing `$` anchor, and a body that is
+/// either a literal or a single `(alt1|alt2|...)` alternation group. Without both
+/// anchors, matching is a substring/prefix/suffix search, same as an unanchored regex;
+/// with both anchors, the whole string (or one alternative) must match exactly.
+///
+/// This is not a general regex engine - just enough to express "one of a short list of
+/// values", the common case for selectors like `surface=~/^(unpaved|gravel|dirt)$/`.
+fn match_value_pattern(pattern: &str, value: &str) -> bool {
+    let anchored_start = pattern.starts_with('^');
+    let anchored_end = pattern.ends_with('$');
+
+    let mut body = pattern;
+    if anchored_start {
+        body = &body[1..];
+    }
+    if anchored_end {
+        body = &body[..body.len() - 1];
+    }
+
+    let alternatives: Vec<&str> = match body.strip_prefix('(').and_then(|b| b.strip_suffix(')')) {
+        Some(inner) => inner.split('|').collect(),
+        None => vec![body],
+    };
+
+    alternatives.into_iter().any(|alt| match (anchored_start, anchored_end) {
+        (true, true) => value == alt,
+        (true, false) => value.starts_with(alt),
+        (false, true) => value.ends_with(alt),
+        (false, false) => value.contains(alt),
+    })
+}
+
+/// A conjunction of [Condition]s plus the penalty applied once every condition matches -
+/// see [Profile::penalty_rules]/[Profile::factor_rules].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rule<'a> {
+    /// Every condition that must hold (ANDed) for this rule to fire. An empty slice
+    /// fires unconditionally.
+    pub conditions: &'a [Condition<'a>],
+
+    /// Multiplier applied when this rule fires, same units as [Penalty::penalty].
+    pub penalty: f32,
+}
+
+impl Rule<'_> {
+    /// Checks whether every one of [Rule::conditions] matches `tags`.
+    fn matches(&self, tags: &HashMap<String, String>) -> bool {
+        self.conditions.iter().all(|c| c.matches(tags))
+    }
+}
+
+/// Numeric multiplier for OSM nodes carrying a specific
+/// [barrier tag](https://wiki.openstreetmap.org/wiki/Key:barrier), e.g. `barrier=gate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Barrier<'a> {
+    /// Key of an OSM node for which this Barrier applies, customarily `"barrier"`.
+    pub key: &'a str,
+
+    /// Value under [Barrier::key] of an OSM node for which this Barrier applies.
+    /// E.g. "gate", "bollard" or "lift_gate".
+    pub value: &'a str,
+
+    /// Multiplier applied to every edge touching a node with this barrier.
+    /// [f32::INFINITY] blocks traversal outright; `1.0` whitelists the barrier, letting
+    /// it through unpenalized.
+    pub penalty: f32,
+}
+
+/// Default speed (km/h) for OSM ways with specific keys and values, consulted by
+/// [Profile::edge_cost] under [WeightMode::Duration]/[WeightMode::Routability] when a way
+/// has no (valid) `maxspeed` tag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speed<'a> {
+    /// Key of an OSM way for which this Speed applies, e.g. `"highway"`.
+    pub key: &'a str,
+
+    /// Value under [Speed::key] of an OSM way for which this Speed applies,
+    /// e.g. `"motorway"` or `"residential"`.
+    pub value: &'a str,
+
+    /// Default speed, in km/h.
+    pub kmh: f32,
+}
+
+/// Selects what [Profile::edge_cost] optimizes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightMode {
+    /// Cost is `distance * `[Penalty] - same preference-weighted-shortest-path behavior
+    /// as every other [Profile] method ([Profile::way_penalty] in particular).
+    #[default]
+    Distance,
+
+    /// Cost is travel duration, derived from `distance` and the effective speed (the
+    /// parsed `maxspeed` tag, falling back to [Profile::speed_profile]) - produces a
+    /// fastest route, ignoring [Penalty] preferences entirely.
+    Duration,
+
+    /// Cost is the [WeightMode::Duration] duration multiplied by [Penalty] - a fastest
+    /// route that still prefers nicer roads among otherwise-equal options.
+    Routability,
+}
+
+/// Result of [Profile::access_level]: how permissive a way or node's access tags are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// No matching [Profile::access_disallowed_values]/[Profile::access_restricted_values]
+    /// value - fully routable.
+    Allowed,
+
+    /// Matches a [Profile::access_restricted_values] value, e.g. `access=destination` -
+    /// still routable, but [Profile::way_penalty] penalizes through-traffic.
+    Restricted,
+
+    /// Matches a [Profile::access_disallowed_values] value, e.g. `access=no` - not routable.
+    Disallowed,
+}
+
+/// Day of the week, for evaluating the weekday part of a `*:conditional` tag (see
+/// [Profile::effective_value]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// Parses an [opening_hours](https://wiki.openstreetmap.org/wiki/Key:opening_hours)
+    /// two-letter weekday abbreviation, e.g. `"Mo"`.
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Mo" => Some(Weekday::Mon),
+            "Tu" => Some(Weekday::Tue),
+            "We" => Some(Weekday::Wed),
+            "Th" => Some(Weekday::Thu),
+            "Fr" => Some(Weekday::Fri),
+            "Sa" => Some(Weekday::Sat),
+            "Su" => Some(Weekday::Sun),
+            _ => None,
+        }
+    }
+
+    /// Position in the week, `Mon = 0` through `Sun = 6`, used to evaluate `Mo-Fr`-style
+    /// weekday ranges (including ones wrapping past `Sun`, e.g. `Fr-Mo`).
+    fn ordinal(self) -> u8 {
+        match self {
+            Weekday::Mon => 0,
+            Weekday::Tue => 1,
+            Weekday::Wed => 2,
+            Weekday::Thu => 3,
+            Weekday::Fri => 4,
+            Weekday::Sat => 5,
+            Weekday::Sun => 6,
+        }
+    }
+}
+
+/// Month of the year, for evaluating the optional month-range part of a `*:conditional`
+/// tag (see [Profile::effective_value]), e.g. `"Jan-Mar"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Month {
+    Jan,
+    Feb,
+    Mar,
+    Apr,
+    May,
+    Jun,
+    Jul,
+    Aug,
+    Sep,
+    Oct,
+    Nov,
+    Dec,
+}
+
+impl Month {
+    /// Parses an [opening_hours](https://wiki.openstreetmap.org/wiki/Key:opening_hours)
+    /// three-letter month abbreviation, e.g. `"Jan"`.
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Jan" => Some(Month::Jan),
+            "Feb" => Some(Month::Feb),
+            "Mar" => Some(Month::Mar),
+            "Apr" => Some(Month::Apr),
+            "May" => Some(Month::May),
+            "Jun" => Some(Month::Jun),
+            "Jul" => Some(Month::Jul),
+            "Aug" => Some(Month::Aug),
+            "Sep" => Some(Month::Sep),
+            "Oct" => Some(Month::Oct),
+            "Nov" => Some(Month::Nov),
+            "Dec" => Some(Month::Dec),
+            _ => None,
+        }
+    }
+
+    /// Position in the year, `Jan = 0` through `Dec = 11`, used to evaluate `Jan-Mar`-style
+    /// month ranges (including ones wrapping past `Dec`, e.g. `Nov-Feb`).
+    fn ordinal(self) -> u8 {
+        match self {
+            Month::Jan => 0,
+            Month::Feb => 1,
+            Month::Mar => 2,
+            Month::Apr => 3,
+            Month::May => 4,
+            Month::Jun => 5,
+            Month::Jul => 6,
+            Month::Aug => 7,
+            Month::Sep => 8,
+            Month::Oct => 9,
+            Month::Nov => 10,
+            Month::Dec => 11,
+        }
+    }
+}
+
+/// A point in time against which [Profile::effective_value] evaluates `*:conditional`
+/// tags - just enough of the calendar to cover the
+/// [opening_hours](https://wiki.openstreetmap.org/wiki/Key:opening_hours) subset
+/// [Profile::effective_value] understands (weekday ranges, time-of-day ranges, month
+/// ranges and `PH`), without pulling in a full date/time library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningHoursInstant {
+    /// Day of the week.
+    pub weekday: Weekday,
+
+    /// Month of the year.
+    pub month: Month,
+
+    /// Hour of the day, `0..=23`.
+    pub hour: u8,
+
+    /// Minute of the hour, `0..=59`.
+    pub minute: u8,
+
+    /// Whether this instant falls on a public holiday, for the `PH` condition.
+    pub is_public_holiday: bool,
+}
+
 /// Turn restriction kind indicator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TurnRestriction {
@@ -83,22 +493,39 @@ pub enum TurnRestriction {
 
     /// The sequence of nodes must be followed after using an edge identified by the first two nodes.
     Mandatory,
+
+    /// A `no_entry` restriction: entering any of the (possibly several) `to` members
+    /// from the (single) `from` member is prohibited.
+    NoEntry,
+
+    /// A `no_exit` restriction: leaving any of the (possibly several) `from` members
+    /// onto the (single) `to` member is prohibited.
+    NoExit,
 }
 
 impl<'a> Profile<'a> {
-    /// Finds the first matching [Penalty] for a way with given tags.
-    /// If there is no matching penalty, or if the way is disallowed
-    /// by the access tags (as determined by [Profile::is_allowed]),
+    /// Finds the first matching [Penalty] for a way with given tags, multiplied by the
+    /// product of every matching [Profile::factors] entry, and by
+    /// [Profile::restricted_access_penalty] if [Profile::access_level] is
+    /// [Access::Restricted]. If there is no matching base penalty, or if the way is
+    /// disallowed by the access tags (as determined by [Profile::is_allowed]),
     /// returns [f32::INFINITY].
     pub fn way_penalty(&self, tags: &HashMap<String, String>) -> f32 {
         let penalty = self.get_penalty(tags);
         if !penalty.is_normal() || !self.is_allowed(tags) {
             return f32::INFINITY;
         }
-        return penalty;
+
+        let mut penalty = penalty * self.get_factor(tags);
+        if self.access_level(tags) == Access::Restricted {
+            penalty *= self.restricted_access_penalty;
+        }
+        penalty
     }
 
-    /// Returns the first matching penalty from way tags, or [f32::INFINITY] otherwise.
+    /// Returns the first matching penalty from way tags, falling back to the first
+    /// firing [Profile::penalty_rules] entry if no [Profile::penalties] entry matches, or
+    /// [f32::INFINITY] if neither does.
     fn get_penalty(&self, tags: &HashMap<String, String>) -> f32 {
         self.penalties
             .iter()
@@ -109,27 +536,339 @@ impl<'a> Profile<'a> {
                     None
                 }
             })
+            .or_else(|| {
+                self.penalty_rules
+                    .iter()
+                    .find(|r| r.matches(tags))
+                    .map(|r| r.penalty)
+            })
             .unwrap_or(f32::INFINITY)
     }
 
-    /// Checks if the way is routable, by considering motor roads ([Profile::disallow_motorroad])
-    /// and access tags ([Profile::access]).
+    /// Returns the product of every [Profile::factors] entry matching the way tags, times
+    /// the product of every firing [Profile::factor_rules] entry, or `1.0` (no effect) if
+    /// none match.
+    fn get_factor(&self, tags: &HashMap<String, String>) -> f32 {
+        let flat: f32 = self
+            .factors
+            .iter()
+            .filter(|f| tags.get(f.key).map(|v| v.as_str()) == Some(f.value))
+            .map(|f| f.penalty)
+            .product();
+        let ruled: f32 = self
+            .factor_rules
+            .iter()
+            .filter(|r| r.matches(tags))
+            .map(|r| r.penalty)
+            .product();
+        flat * ruled
+    }
+
+    /// Cost of an edge with `distance` (in the same units as
+    /// [earth_distance](crate::earth_distance), i.e. kilometers) along a way with `tags`,
+    /// according to [Profile::weight_mode]. Returns [f32::INFINITY] if the way is not
+    /// routable, same as [Profile::way_penalty].
+    pub fn edge_cost(&self, tags: &HashMap<String, String>, distance: f32) -> f32 {
+        let penalty = self.way_penalty(tags);
+        if !penalty.is_finite() {
+            return f32::INFINITY;
+        }
+
+        match self.weight_mode {
+            WeightMode::Distance => distance * penalty,
+            WeightMode::Duration => distance / self.effective_speed(tags),
+            WeightMode::Routability => distance / self.effective_speed(tags) * penalty,
+        }
+    }
+
+    /// Effective speed (km/h) of a way with `tags`: the parsed `maxspeed` tag (handling
+    /// `mph`/`knots` suffixes), falling back to the first matching [Profile::speed_profile]
+    /// entry, or [DEFAULT_SPEED_KMH] if neither is present or valid.
+    fn effective_speed(&self, tags: &HashMap<String, String>) -> f32 {
+        tags.get("maxspeed")
+            .and_then(|v| Self::parse_maxspeed(v))
+            .or_else(|| {
+                self.speed_profile.iter().find_map(|s| {
+                    (tags.get(s.key).map(|v| v.as_str()) == Some(s.value)).then_some(s.kmh)
+                })
+            })
+            .unwrap_or(DEFAULT_SPEED_KMH)
+    }
+
+    /// Parses an OSM [maxspeed](https://wiki.openstreetmap.org/wiki/Key:maxspeed) value
+    /// into km/h, converting `mph` and `knots` suffixes. Returns `None` for anything else
+    /// (e.g. `"none"`, `"signals"`, `"walk"`, or a malformed number).
+    fn parse_maxspeed(value: &str) -> Option<f32> {
+        let value = value.trim();
+        if let Some(mph) = value.strip_suffix("mph") {
+            mph.trim().parse::<f32>().ok().map(|v| v * KMH_PER_MPH)
+        } else if let Some(knots) = value.strip_suffix("knots") {
+            knots.trim().parse::<f32>().ok().map(|v| v * KMH_PER_KNOT)
+        } else {
+            value.parse::<f32>().ok()
+        }
+    }
+
+    /// Checks if the way is routable, by considering motor roads ([Profile::disallow_motorroad]),
+    /// access tags ([Profile::access_level]) and vehicle dimensions
+    /// ([Profile::fits_vehicle_dimensions]). A thin wrapper: [Access::Restricted] still
+    /// counts as allowed here - it is [Profile::way_penalty] that penalizes it.
     pub fn is_allowed(&self, tags: &HashMap<String, String>) -> bool {
         // Check against the motorroad tag
         if self.disallow_motorroad && tags.get("motorroad").map(|v| v.as_str()) == Some("yes") {
             return false;
         }
 
-        // Check against the access tags
+        if self.access_level(tags) == Access::Disallowed {
+            return false;
+        }
+
+        self.fits_vehicle_dimensions(tags)
+    }
+
+    /// Classifies a way or node's access tags into an [Access] level, by matching the
+    /// most specific mode in [Profile::access] against [Profile::access_disallowed_values]
+    /// and [Profile::access_restricted_values] (in that order). Absent any match
+    /// (including no relevant tag at all), returns [Access::Allowed]. Each mode's
+    /// `:conditional` variant is consulted first, see [Profile::effective_value].
+    pub fn access_level(&self, tags: &HashMap<String, String>) -> Access {
+        match self
+            .access
+            .iter()
+            .rev()
+            .find_map(|&mode| self.effective_value(tags, mode))
+        {
+            Some(v) if self.access_disallowed_values.contains(&v) => Access::Disallowed,
+            Some(v) if self.access_restricted_values.contains(&v) => Access::Restricted,
+            _ => Access::Allowed,
+        }
+    }
+
+    /// Returns the effective value of the `key` tag: `tags[key:conditional]` if
+    /// [Profile::query_time] is set and one of its `<value> @ (<condition>)` clauses
+    /// (see [Profile::evaluate_conditional]) matches, otherwise the plain `tags[key]`.
+    fn effective_value<'t>(&self, tags: &'t HashMap<String, String>, key: &str) -> Option<&'t str> {
+        if let Some(conditional) = tags.get(&format!("{key}:conditional")) {
+            if let Some(value) = Self::evaluate_conditional(conditional, self.query_time) {
+                return Some(value);
+            }
+        }
+        tags.get(key).map(|v| v.as_str())
+    }
+
+    /// Evaluates a `*:conditional` tag value, e.g.
+    /// `"no @ (Mo-Fr 07:00-19:00); no @ (PH)"`, against `query_time`: splits on `;` into
+    /// independent `<value> @ (<condition>)` clauses, evaluating each in order, and
+    /// returns the `<value>` of the *last* whose `<condition>` matches - same
+    /// last-one-wins override semantics as
+    /// [opening_hours](https://wiki.openstreetmap.org/wiki/Key:opening_hours) itself. A
+    /// `<condition>` is itself a whitespace-separated, ANDed list of weekday ranges
+    /// (`Mo-Fr`, `Sa`, `Mo,We,Fr`), time-of-day ranges (`HH:MM-HH:MM`, possibly wrapping
+    /// past midnight), month ranges (`Jan-Mar`), and/or `PH`. Returns `None` if
+    /// `query_time` is unset, the tag is malformed, or no clause matches - callers should
+    /// fall back to the unconditional tag in that case.
+    fn evaluate_conditional(raw: &str, query_time: Option<OpeningHoursInstant>) -> Option<&str> {
+        let query_time = query_time?;
+        let mut result = None;
+        for clause in raw.split(';') {
+            let Some((value, condition)) = clause.trim().split_once('@') else {
+                continue;
+            };
+            let condition = condition.trim();
+            let Some(condition) = condition
+                .strip_prefix('(')
+                .and_then(|c| c.strip_suffix(')'))
+            else {
+                continue;
+            };
+            if Self::condition_matches(condition, query_time) {
+                result = Some(value.trim());
+            }
+        }
+        result
+    }
+
+    /// Checks every whitespace-separated token of a `<condition>` against `at`, ANDing
+    /// them together - see [Profile::evaluate_conditional].
+    fn condition_matches(condition: &str, at: OpeningHoursInstant) -> bool {
+        condition.split_whitespace().all(|token| match token {
+            "PH" => at.is_public_holiday,
+            _ if token.contains(':') => Self::time_range_matches(token, at),
+            _ if Self::is_month_token(token) => Self::month_list_matches(token, at.month),
+            _ => Self::weekday_list_matches(token, at.weekday),
+        })
+    }
+
+    /// Matches a comma-separated list of weekdays/weekday ranges, e.g. `"Mo-Fr"` or
+    /// `"Mo,We,Fr"`, against `weekday`.
+    fn weekday_list_matches(token: &str, weekday: Weekday) -> bool {
+        token.split(',').any(|part| match part.split_once('-') {
+            Some((start, end)) => match (Weekday::parse(start), Weekday::parse(end)) {
+                (Some(start), Some(end)) => {
+                    let (w, s, e) = (weekday.ordinal(), start.ordinal(), end.ordinal());
+                    if s <= e {
+                        w >= s && w <= e
+                    } else {
+                        // Wraps past Sun, e.g. "Fr-Mo".
+                        w >= s || w <= e
+                    }
+                }
+                _ => false,
+            },
+            None => Weekday::parse(part) == Some(weekday),
+        })
+    }
+
+    /// Returns true if `token` looks like a month/month-range rather than a weekday
+    /// range, by checking whether its first comma-/dash-separated part parses as a
+    /// [Month] - used by [Profile::condition_matches] to tell `"Jan-Mar"` apart from
+    /// `"Mo-Fr"`.
+    fn is_month_token(token: &str) -> bool {
+        let first = token.split(',').next().unwrap_or(token);
+        let first = first.split('-').next().unwrap_or(first);
+        Month::parse(first).is_some()
+    }
+
+    /// Matches a comma-separated list of months/month ranges, e.g. `"Jan-Mar"` or
+    /// `"Jan,Jul"`, against `month`.
+    fn month_list_matches(token: &str, month: Month) -> bool {
+        token.split(',').any(|part| match part.split_once('-') {
+            Some((start, end)) => match (Month::parse(start), Month::parse(end)) {
+                (Some(start), Some(end)) => {
+                    let (m, s, e) = (month.ordinal(), start.ordinal(), end.ordinal());
+                    if s <= e {
+                        m >= s && m <= e
+                    } else {
+                        // Wraps past Dec, e.g. "Nov-Feb".
+                        m >= s || m <= e
+                    }
+                }
+                _ => false,
+            },
+            None => Month::parse(part) == Some(month),
+        })
+    }
+
+    /// Matches a comma-separated list of `"HH:MM-HH:MM"` time-of-day ranges (each possibly
+    /// wrapping past midnight, e.g. `"22:00-06:00"`), e.g. `"06:00-09:00,16:00-19:00"`,
+    /// against `at`'s time of day.
+    fn time_range_matches(token: &str, at: OpeningHoursInstant) -> bool {
+        let now = at.hour as u16 * 60 + at.minute as u16;
+
+        token.split(',').any(|part| {
+            let Some((start, end)) = part.split_once('-') else {
+                return false;
+            };
+            let (Some(start), Some(end)) = (Self::parse_time(start), Self::parse_time(end)) else {
+                return false;
+            };
+
+            if start <= end {
+                now >= start && now < end
+            } else {
+                now >= start || now < end
+            }
+        })
+    }
+
+    /// Parses an `"HH:MM"` time of day into minutes since midnight.
+    fn parse_time(value: &str) -> Option<u16> {
+        let (h, m) = value.split_once(':')?;
+        Some(h.parse::<u16>().ok()? * 60 + m.parse::<u16>().ok()?)
+    }
+
+    /// Checks a way's `maxheight`/`maxwidth`/`maxlength`/`maxweight` tags against
+    /// [Profile::vehicle_height]/[Profile::vehicle_width]/[Profile::vehicle_length]/
+    /// [Profile::vehicle_weight] (whichever are set), plus `hgv=no` if any vehicle
+    /// dimension is configured. Only the plain tags are considered -
+    /// `maxweight:conditional`-style time-dependent restrictions are ignored.
+    fn fits_vehicle_dimensions(&self, tags: &HashMap<String, String>) -> bool {
+        let is_goods_vehicle = self.vehicle_height.is_some()
+            || self.vehicle_width.is_some()
+            || self.vehicle_length.is_some()
+            || self.vehicle_weight.is_some();
+        if is_goods_vehicle && tags.get("hgv").map(|v| v.as_str()) == Some("no") {
+            return false;
+        }
+
+        Self::fits_dimension(tags.get("maxheight"), self.vehicle_height)
+            && Self::fits_dimension(tags.get("maxwidth"), self.vehicle_width)
+            && Self::fits_dimension(tags.get("maxlength"), self.vehicle_length)
+            && Self::fits_dimension(tags.get("maxweight"), self.vehicle_weight)
+    }
+
+    /// Checks a single dimension tag against a configured vehicle dimension. Returns `true`
+    /// (unrestricted) if `vehicle` is `None`, if the tag is absent, if its value is
+    /// `"default"`, `"none"` or `"signed"` (no numeric limit known), or if it fails to
+    /// parse - only a successfully parsed tag value smaller than `vehicle` blocks routing.
+    fn fits_dimension(tag_value: Option<&String>, vehicle: Option<f32>) -> bool {
+        let Some(vehicle) = vehicle else {
+            return true;
+        };
+
+        match tag_value.map(|v| v.as_str()) {
+            None | Some("default") | Some("none") | Some("signed") => true,
+            Some(v) => Self::parse_dimension(v).map_or(true, |limit| limit >= vehicle),
+        }
+    }
+
+    /// Parses an OSM dimension value (`maxheight`/`maxwidth`/`maxlength` in meters,
+    /// `maxweight` in tonnes) with an optional `m`/`t` suffix, e.g. `"4.5"` or `"4.5 m"`.
+    fn parse_dimension(value: &str) -> Option<f32> {
+        let value = value.trim();
+        if let Some(m) = value.strip_suffix('m') {
+            m.trim().parse::<f32>().ok()
+        } else if let Some(t) = value.strip_suffix('t') {
+            t.trim().parse::<f32>().ok()
+        } else {
+            value.parse::<f32>().ok()
+        }
+    }
+
+    /// Multiplier applied to every edge touching a node with `tags`, due to a
+    /// [Profile::barriers] match, e.g. `barrier=gate`.
+    ///
+    /// The node's own access tags take precedence over [Profile::barriers] - same
+    /// hierarchy as [Profile::is_allowed] - so a `barrier=bollard` node tagged
+    /// `bicycle=yes` stays routable for a bicycle profile even if bollards are otherwise
+    /// blocked. Absent an access override, returns [f32::INFINITY] for a blocked
+    /// [Barrier], `1.0` for a whitelisted or absent one, and the matching [Barrier]'s
+    /// multiplier otherwise.
+    ///
+    /// [GraphBuilder](crate::osm::GraphBuilder) calls this for every node carrying tags
+    /// while creating the edges around it - see `GraphBuilder::create_edges`.
+    pub fn node_penalty(&self, tags: &HashMap<String, String>) -> f32 {
         match self
             .access
             .iter()
             .rev()
             .find_map(|&mode| tags.get(mode).map(|v| v.as_str()))
         {
-            Some("no") | Some("private") => false,
-            _ => true,
+            Some(v) if self.access_disallowed_values.contains(&v) => return f32::INFINITY,
+            Some(v) if self.access_restricted_values.contains(&v) => {
+                return self.restricted_access_penalty
+            }
+            Some(_) => return 1.0,
+            None => {}
         }
+
+        self.barriers
+            .iter()
+            .find_map(|b| {
+                if tags.get(b.key).map(|v| v.as_str()) == Some(b.value) {
+                    Some(b.penalty)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(1.0)
+    }
+
+    /// Checks if a node with `tags` is routable at all, by considering [Profile::node_penalty].
+    /// A thin wrapper: a [Barrier] with a finite, non-`1.0` multiplier still counts as allowed
+    /// here - it is [Profile::node_penalty] itself that penalizes it.
+    pub fn node_is_allowed(&self, tags: &HashMap<String, String>) -> bool {
+        self.node_penalty(tags).is_finite()
     }
 
     /// Checks if a way is traversable forward (first return value) and
@@ -182,17 +921,19 @@ impl<'a> Profile<'a> {
     }
 
     /// Returns the value of the most specific "oneway:MODE" tag (based on [Profile::access]),
-    /// falling back to simply "oneway", and returning an empty string if no relevant tag was found.
+    /// falling back to simply "oneway", and returning an empty string if no relevant tag was
+    /// found. Each candidate tag's `:conditional` variant is consulted first, see
+    /// [Profile::effective_value].
     fn get_active_oneway_value<'t>(&self, tags: &'t HashMap<String, String>) -> &'t str {
         if self.apply_foot_exceptions() {
             // foot profile exception - only consider "oneway:foot" and "oneway" in select cases
-            if let Some(oneway_foot) = tags.get("oneway:foot") {
-                return oneway_foot.as_str();
+            if let Some(oneway_foot) = self.effective_value(tags, "oneway:foot") {
+                return oneway_foot;
             }
 
             if Self::allow_generic_oneway_to_apply_on_foot(tags) {
-                if let Some(oneway) = tags.get("oneway") {
-                    return oneway.as_str();
+                if let Some(oneway) = self.effective_value(tags, "oneway") {
+                    return oneway;
                 }
             }
 
@@ -202,9 +943,8 @@ impl<'a> Profile<'a> {
                 .iter()
                 .rev()
                 .filter(|&&mode| mode != "access")
-                .find_map(|&mode| tags.get(&format!("oneway:{}", mode)))
-                .or_else(|| tags.get("oneway"))
-                .map(|oneway_tag| oneway_tag.as_str())
+                .find_map(|&mode| self.effective_value(tags, &format!("oneway:{}", mode)))
+                .or_else(|| self.effective_value(tags, "oneway"))
                 .unwrap_or("")
         }
     }
@@ -250,6 +990,15 @@ impl<'a> Profile<'a> {
             .split_once('_')
             .unwrap_or(("", ""));
 
+        // no_entry/no_exit carry several `to`/`from` members respectively - handled
+        // separately from the single-from/single-to restrictions below, see
+        // GraphBuilder::get_ordered_restriction_members_multi.
+        match (kind, description) {
+            ("no", "entry") => return TurnRestriction::NoEntry,
+            ("no", "exit") => return TurnRestriction::NoExit,
+            _ => {}
+        }
+
         // Check that the description is supported
         match description {
             "right_turn" | "left_turn" | "u_turn" | "straight_on" => {}
@@ -264,32 +1013,81 @@ impl<'a> Profile<'a> {
         };
     }
 
-    /// Returns true if [Profile::access] intersects with any mode present in the `except` tag.
-    /// If the tag is missing, returns false.
+    /// Additive turn cost at a junction, layered on top of (and independent from) hard
+    /// [TurnRestriction]s: scales [Profile::turn_penalty] by the sharpness of the turn
+    /// between `from_heading` and `to_heading` (compass bearings in degrees, `0` = north,
+    /// increasing clockwise), applying [Profile::turn_bias] for a left turn; snaps to the
+    /// full [Profile::u_turn_penalty] once the turn exceeds [U_TURN_ANGLE_DEG]; and adds
+    /// [Profile::traffic_signal_penalty] if `via_node_tags` carries `highway=traffic_signals`
+    /// or `highway=stop`.
+    ///
+    /// Called by [GraphBuilder::finish](crate::osm::GraphBuilder::finish) at every node, with
+    /// headings computed on the fly from the endpoints' coordinates - a junction touching 3 or
+    /// more distinct neighbors has all but one of its incoming directions cloned first
+    /// (the same phantom-node trick used for hard [TurnRestriction]s), so each direction is
+    /// only charged for its own turn.
+    pub fn turn_cost(
+        &self,
+        from_heading: f32,
+        to_heading: f32,
+        via_node_tags: &HashMap<String, String>,
+    ) -> f32 {
+        let angle = Self::normalize_turn_angle(to_heading - from_heading);
+
+        let mut cost = if angle.abs() > U_TURN_ANGLE_DEG {
+            self.u_turn_penalty
+        } else {
+            let bias = if angle < 0.0 { self.turn_bias } else { 1.0 };
+            self.turn_penalty * (angle.abs() / 180.0) * bias
+        };
+
+        if matches!(
+            via_node_tags.get("highway").map(|v| v.as_str()),
+            Some("traffic_signals") | Some("stop")
+        ) {
+            cost += self.traffic_signal_penalty;
+        }
+
+        cost
+    }
+
+    /// Normalizes the difference of two compass bearings (degrees) into `(-180, 180]`:
+    /// negative for a left turn, positive for a right turn, `±180` for a u-turn.
+    fn normalize_turn_angle(diff: f32) -> f32 {
+        let mut angle = diff % 360.0;
+        if angle > 180.0 {
+            angle -= 360.0;
+        } else if angle <= -180.0 {
+            angle += 360.0;
+        }
+        angle
+    }
+
+    /// Returns true if [Profile::access] intersects with any mode present in the `except`
+    /// tag. If the tag is missing, returns false. Consults `except:conditional` first,
+    /// see [Profile::effective_value].
     pub fn is_exempted(&self, tags: &HashMap<String, String>) -> bool {
-        tags.get("except")
-            .map_or("", |v| v.as_str())
+        self.effective_value(tags, "except")
+            .unwrap_or("")
             .split(';')
             .any(|exempted_type| self.access.contains(&exempted_type))
     }
 
     /// Returns the value of the most specific "restriction:MODE" tag (based on [Profile::access]),
     /// falling back to simply "restriction", and returning an empty string if no relevant tag
-    /// was found.
+    /// was found. Each candidate tag's `:conditional` variant is consulted first, see
+    /// [Profile::effective_value].
     fn get_active_restriction_tag<'t>(&self, tags: &'t HashMap<String, String>) -> &'t str {
         if self.apply_foot_exceptions() {
             // foot profile exception - only consider "restriction:foot"
-            tags.get("restriction:foot")
-                .map(|v| v.as_str())
-                .unwrap_or("")
+            self.effective_value(tags, "restriction:foot").unwrap_or("")
         } else {
             self.access
                 .iter()
                 .rev()
                 .filter(|&&mode| mode != "access")
-                .find_map(|&mode| tags.get(&format!("restriction:{}", mode)))
-                .or_else(|| tags.get("restriction"))
-                .map(|v| v.as_str())
+                .find_map(|&mode| self.effective_value(tags, &format!("restriction:{}", mode)))
+                .or_else(|| self.effective_value(tags, "restriction"))
                 .unwrap_or("")
         }
     }
@@ -385,9 +1183,31 @@ pub const CAR_PROFILE: Profile = Profile {
             penalty: 20.0,
         },
     ],
+    factors: &[],
+    barriers: &[
+        Barrier { key: "barrier", value: "gate", penalty: f32::INFINITY },
+        Barrier { key: "barrier", value: "bollard", penalty: f32::INFINITY },
+        Barrier { key: "barrier", value: "lift_gate", penalty: f32::INFINITY },
+    ],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "vehicle", "motor_vehicle", "motorcar"],
+    access_disallowed_values: &["no", "private", "agricultural", "forestry"],
+    access_restricted_values: &["destination", "delivery", "customers"],
+    restricted_access_penalty: 5.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: false,
     disable_restrictions: false,
+    u_turn_penalty: 0.1,
+    turn_penalty: 0.02,
+    turn_bias: 1.3,
+    traffic_signal_penalty: 0.01,
+    query_time: None,
 };
 
 /// Example routing [Profile] for buses, without high preference differences for different
@@ -476,6 +1296,16 @@ pub const BUS_PROFILE: Profile = Profile {
             penalty: 5.0,
         },
     ],
+    factors: &[],
+    barriers: &[
+        Barrier { key: "barrier", value: "gate", penalty: f32::INFINITY },
+        Barrier { key: "barrier", value: "bollard", penalty: f32::INFINITY },
+        Barrier { key: "barrier", value: "lift_gate", penalty: f32::INFINITY },
+    ],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &[
         "access",
         "vehicle",
@@ -484,8 +1314,20 @@ pub const BUS_PROFILE: Profile = Profile {
         "bus",
         "routing:ztm",
     ],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &["destination", "delivery"],
+    restricted_access_penalty: 3.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: false,
     disable_restrictions: false,
+    u_turn_penalty: 0.1,
+    turn_penalty: 0.02,
+    turn_bias: 1.2,
+    traffic_signal_penalty: 0.015,
+    query_time: None,
 };
 
 /// Example routing [Profile] for bicycles, with preferences for quieter roads
@@ -589,9 +1431,60 @@ pub const BICYCLE_PROFILE: Profile = Profile {
             penalty: 2.0,
         },
     ],
+    // Graded way-quality factors: cyclists pay a steep surcharge for loose or slippery
+    // surfaces, on top of the highway-type penalty above. Unlisted surface/smoothness/
+    // tracktype values (including a missing tag) don't match any entry here, leaving the
+    // cost unchanged - see Profile::get_factor.
+    factors: &[
+        Penalty { key: "surface", value: "unpaved", penalty: 1.5 },
+        Penalty { key: "surface", value: "compacted", penalty: 1.2 },
+        Penalty { key: "surface", value: "gravel", penalty: 2.0 },
+        Penalty { key: "surface", value: "fine_gravel", penalty: 1.5 },
+        Penalty { key: "surface", value: "pebblestone", penalty: 2.0 },
+        Penalty { key: "surface", value: "ground", penalty: 2.5 },
+        Penalty { key: "surface", value: "earth", penalty: 2.5 },
+        Penalty { key: "surface", value: "dirt", penalty: 2.5 },
+        Penalty { key: "surface", value: "grass", penalty: 3.0 },
+        Penalty { key: "surface", value: "sand", penalty: 4.0 },
+        Penalty { key: "surface", value: "mud", penalty: 8.0 },
+        Penalty { key: "surface", value: "ice", penalty: 10.0 },
+        Penalty { key: "surface", value: "salt", penalty: 3.0 },
+        Penalty { key: "surface", value: "snow", penalty: 6.0 },
+        Penalty { key: "surface", value: "woodchips", penalty: 2.0 },
+        Penalty { key: "smoothness", value: "bad", penalty: 2.0 },
+        Penalty { key: "smoothness", value: "very_bad", penalty: 4.0 },
+        Penalty { key: "smoothness", value: "horrible", penalty: 8.0 },
+        Penalty { key: "smoothness", value: "very_horrible", penalty: 16.0 },
+        Penalty { key: "tracktype", value: "grade1", penalty: 1.0 },
+        Penalty { key: "tracktype", value: "grade2", penalty: 1.2 },
+        Penalty { key: "tracktype", value: "grade3", penalty: 1.5 },
+        Penalty { key: "tracktype", value: "grade4", penalty: 2.5 },
+        Penalty { key: "tracktype", value: "grade5", penalty: 4.0 },
+    ],
+    barriers: &[
+        Barrier { key: "barrier", value: "gate", penalty: f32::INFINITY },
+        Barrier { key: "barrier", value: "bollard", penalty: 1.0 },
+        Barrier { key: "barrier", value: "lift_gate", penalty: 1.0 },
+    ],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "vehicle", "bicycle"],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &["destination", "customers"],
+    restricted_access_penalty: 2.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: true,
     disable_restrictions: false,
+    u_turn_penalty: 0.02,
+    turn_penalty: 0.005,
+    turn_bias: 1.1,
+    traffic_signal_penalty: 0.005,
+    query_time: None,
 };
 
 /// Example routing [Profile] for walking, with preferences for quieter roads
@@ -710,9 +1603,60 @@ pub const FOOT_PROFILE: Profile = Profile {
             penalty: 1.1,
         },
     ],
+    // Graded way-quality factors, milder than BICYCLE_PROFILE's since walking doesn't
+    // suffer from rolling resistance the way cycling does - pedestrians mostly just want
+    // to avoid mud, snow and ice. Unlisted values (including a missing tag) leave the
+    // cost unchanged - see Profile::get_factor.
+    factors: &[
+        Penalty { key: "surface", value: "unpaved", penalty: 1.1 },
+        Penalty { key: "surface", value: "compacted", penalty: 1.0 },
+        Penalty { key: "surface", value: "gravel", penalty: 1.1 },
+        Penalty { key: "surface", value: "fine_gravel", penalty: 1.05 },
+        Penalty { key: "surface", value: "pebblestone", penalty: 1.2 },
+        Penalty { key: "surface", value: "ground", penalty: 1.2 },
+        Penalty { key: "surface", value: "earth", penalty: 1.2 },
+        Penalty { key: "surface", value: "dirt", penalty: 1.2 },
+        Penalty { key: "surface", value: "grass", penalty: 1.3 },
+        Penalty { key: "surface", value: "sand", penalty: 1.5 },
+        Penalty { key: "surface", value: "mud", penalty: 2.0 },
+        Penalty { key: "surface", value: "ice", penalty: 3.0 },
+        Penalty { key: "surface", value: "salt", penalty: 1.2 },
+        Penalty { key: "surface", value: "snow", penalty: 2.0 },
+        Penalty { key: "surface", value: "woodchips", penalty: 1.1 },
+        Penalty { key: "smoothness", value: "bad", penalty: 1.1 },
+        Penalty { key: "smoothness", value: "very_bad", penalty: 1.5 },
+        Penalty { key: "smoothness", value: "horrible", penalty: 2.5 },
+        Penalty { key: "smoothness", value: "very_horrible", penalty: 4.0 },
+        Penalty { key: "tracktype", value: "grade1", penalty: 1.0 },
+        Penalty { key: "tracktype", value: "grade2", penalty: 1.0 },
+        Penalty { key: "tracktype", value: "grade3", penalty: 1.1 },
+        Penalty { key: "tracktype", value: "grade4", penalty: 1.2 },
+        Penalty { key: "tracktype", value: "grade5", penalty: 1.3 },
+    ],
+    barriers: &[
+        Barrier { key: "barrier", value: "gate", penalty: 1.0 },
+        Barrier { key: "barrier", value: "bollard", penalty: 1.0 },
+        Barrier { key: "barrier", value: "lift_gate", penalty: 1.0 },
+    ],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "foot"],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &["destination", "customers"],
+    restricted_access_penalty: 1.5,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: true,
     disable_restrictions: false,
+    u_turn_penalty: 0.0,
+    turn_penalty: 0.0,
+    turn_bias: 1.0,
+    traffic_signal_penalty: 0.0,
+    query_time: None,
 };
 
 /// Example simple routing [Profile] for different kinds of trains.
@@ -740,9 +1684,27 @@ pub const RAILWAY_PROFILE: Profile = Profile {
             penalty: 1.0,
         },
     ],
+    factors: &[],
+    barriers: &[],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "train"],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &[],
+    restricted_access_penalty: 1.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: false,
     disable_restrictions: false,
+    u_turn_penalty: 0.0,
+    turn_penalty: 0.0,
+    turn_bias: 1.0,
+    traffic_signal_penalty: 0.0,
+    query_time: None,
 };
 
 /// Example simple routing [Profile] for routing over subway lines.
@@ -760,9 +1722,27 @@ pub const TRAM_PROFILE: Profile = Profile {
             penalty: 1.0,
         },
     ],
+    factors: &[],
+    barriers: &[],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "tram"],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &[],
+    restricted_access_penalty: 1.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: false,
     disable_restrictions: false,
+    u_turn_penalty: 0.0,
+    turn_penalty: 0.0,
+    turn_bias: 1.0,
+    traffic_signal_penalty: 0.0,
+    query_time: None,
 };
 
 /// Example simple routing [Profile] for routing over tram and light rail lines.
@@ -773,14 +1753,36 @@ pub const SUBWAY_PROFILE: Profile = Profile {
         value: "subway",
         penalty: 1.0,
     }],
+    factors: &[],
+    barriers: &[],
+    weight_mode: WeightMode::Distance,
+    speed_profile: &[],
+    penalty_rules: &[],
+    factor_rules: &[],
     access: &["access", "subway"],
+    access_disallowed_values: &["no", "private"],
+    access_restricted_values: &[],
+    restricted_access_penalty: 1.0,
+    vehicle_height: None,
+    vehicle_width: None,
+    vehicle_length: None,
+    vehicle_weight: None,
     disallow_motorroad: false,
     disable_restrictions: false,
+    u_turn_penalty: 0.0,
+    turn_penalty: 0.0,
+    turn_bias: 1.0,
+    traffic_signal_penalty: 0.0,
+    query_time: None,
 };
 
 #[cfg(test)]
 mod tests {
-    use super::{Penalty, Profile, TurnRestriction, FOOT_PROFILE};
+    use super::{
+        match_value_pattern, Access, Barrier, Condition, Month, OpeningHoursInstant, Penalty,
+        Profile, Rule, Speed, TurnRestriction, Weekday, WeightMode, BICYCLE_PROFILE, CAR_PROFILE,
+        FOOT_PROFILE,
+    };
     use std::collections::HashMap;
 
     const TEST_PROFILE: Profile = Profile {
@@ -797,9 +1799,31 @@ mod tests {
                 penalty: 2.0,
             },
         ],
+        factors: &[],
+        barriers: &[
+            Barrier { key: "barrier", value: "gate", penalty: f32::INFINITY },
+            Barrier { key: "barrier", value: "bollard", penalty: 1.0 },
+            Barrier { key: "barrier", value: "kissing_gate", penalty: 1.5 },
+        ],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
         access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
         disallow_motorroad: false,
         disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
     };
 
     const TEST_PROFILE_WITHOUT_MOTORROAD: Profile = Profile {
@@ -816,9 +1840,170 @@ mod tests {
                 penalty: 2.0,
             },
         ],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
         access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
         disallow_motorroad: true,
         disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_WITH_FACTORS: Profile = Profile {
+        name: "cat",
+        penalties: &[
+            Penalty {
+                key: "highway",
+                value: "track",
+                penalty: 2.0,
+            },
+        ],
+        factors: &[
+            Penalty { key: "surface", value: "mud", penalty: 8.0 },
+            Penalty { key: "smoothness", value: "bad", penalty: 2.0 },
+        ],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_WITH_RULES: Profile = Profile {
+        name: "cat",
+        penalties: &[
+            Penalty {
+                key: "highway",
+                value: "residential",
+                penalty: 1.0,
+            },
+        ],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        // Falls back to a rule only once no flat Penalty matches.
+        penalty_rules: &[
+            Rule {
+                conditions: &[Condition::KeyPresent("junction"), Condition::KeyAbsent("highway")],
+                penalty: 3.0,
+            },
+            Rule {
+                conditions: &[Condition::Regex("surface", "^(unpaved|gravel|dirt)$")],
+                penalty: 4.0,
+            },
+        ],
+        factor_rules: &[Rule {
+            conditions: &[Condition::NotEquals("smoothness", "good")],
+            penalty: 2.0,
+        }],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_DURATION: Profile = Profile {
+        name: "cat",
+        penalties: &[
+            Penalty {
+                key: "highway",
+                value: "footway",
+                penalty: 1.0,
+            },
+            Penalty {
+                key: "highway",
+                value: "path",
+                penalty: 2.0,
+            },
+        ],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Duration,
+        speed_profile: &[Speed { key: "highway", value: "path", kmh: 10.0 }],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_ROUTABILITY: Profile = Profile {
+        name: "cat",
+        penalties: TEST_PROFILE_DURATION.penalties,
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Routability,
+        speed_profile: TEST_PROFILE_DURATION.speed_profile,
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
     };
 
     const TEST_PROFILE_WITHOUT_RESTRICTIONS: Profile = Profile {
@@ -835,9 +2020,150 @@ mod tests {
                 penalty: 2.0,
             },
         ],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
         access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
         disallow_motorroad: false,
         disable_restrictions: true,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_WITH_TURN_COSTS: Profile = Profile {
+        name: "cat",
+        penalties: &[Penalty {
+            key: "highway",
+            value: "path",
+            penalty: 1.0,
+        }],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 10.0,
+        turn_penalty: 1.0,
+        turn_bias: 2.0,
+        traffic_signal_penalty: 0.5,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_WITH_RESTRICTED_ACCESS: Profile = Profile {
+        name: "cat",
+        penalties: &[Penalty {
+            key: "highway",
+            value: "path",
+            penalty: 2.0,
+        }],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &["destination", "customers"],
+        restricted_access_penalty: 5.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    const TEST_PROFILE_TRUCK: Profile = Profile {
+        name: "cat",
+        penalties: &[Penalty {
+            key: "highway",
+            value: "path",
+            penalty: 1.0,
+        }],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: Some(4.0),
+        vehicle_width: Some(2.5),
+        vehicle_length: Some(12.0),
+        vehicle_weight: Some(7.5),
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: None,
+    };
+
+    // Wednesday 10:00, not a public holiday.
+    const TEST_PROFILE_AT_TIME: Profile = Profile {
+        name: "cat",
+        penalties: &[],
+        factors: &[],
+        barriers: &[],
+        weight_mode: WeightMode::Distance,
+        speed_profile: &[],
+        penalty_rules: &[],
+        factor_rules: &[],
+        access: &["access", "cat"],
+        access_disallowed_values: &["no", "private"],
+        access_restricted_values: &[],
+        restricted_access_penalty: 1.0,
+        vehicle_height: None,
+        vehicle_width: None,
+        vehicle_length: None,
+        vehicle_weight: None,
+        disallow_motorroad: false,
+        disable_restrictions: false,
+        u_turn_penalty: 0.0,
+        turn_penalty: 0.0,
+        turn_bias: 1.0,
+        traffic_signal_penalty: 0.0,
+        query_time: Some(OpeningHoursInstant {
+            weekday: Weekday::Wed,
+            month: Month::Jun,
+            hour: 10,
+            minute: 0,
+            is_public_holiday: false,
+        }),
     };
 
     macro_rules! tags {
@@ -876,6 +2202,147 @@ mod tests {
         );
     }
 
+    #[test]
+    fn way_penalty_with_factors() {
+        assert_eq!(
+            TEST_PROFILE_WITH_FACTORS.way_penalty(&tags! {"highway": "track"}),
+            2.0,
+        );
+        assert_eq!(
+            TEST_PROFILE_WITH_FACTORS.way_penalty(&tags! {"highway": "track", "surface": "mud"}),
+            16.0,
+        );
+        assert_eq!(
+            TEST_PROFILE_WITH_FACTORS.way_penalty(
+                &tags! {"highway": "track", "surface": "mud", "smoothness": "bad"}
+            ),
+            32.0,
+        );
+        // A factor matching an unroutable way has no effect - still INFINITY.
+        assert_eq!(
+            TEST_PROFILE_WITH_FACTORS.way_penalty(&tags! {"surface": "mud"}),
+            f32::INFINITY,
+        );
+    }
+
+    #[test]
+    fn way_penalty_with_rules() {
+        // A flat Penalty still wins over a rule when both could apply.
+        assert_eq!(
+            TEST_PROFILE_WITH_RULES.way_penalty(&tags! {"highway": "residential"}),
+            1.0,
+        );
+        // No Penalty matches "junction" alone, but the KeyPresent/KeyAbsent rule does.
+        assert_eq!(
+            TEST_PROFILE_WITH_RULES.way_penalty(&tags! {"junction": "roundabout"}),
+            3.0,
+        );
+        // A highway tag disqualifies the KeyAbsent("highway") condition, falling through
+        // to the Regex rule instead.
+        assert_eq!(
+            TEST_PROFILE_WITH_RULES
+                .way_penalty(&tags! {"highway": "track", "surface": "gravel"}),
+            4.0,
+        );
+        // Neither Penalty nor any rule matches.
+        assert_eq!(TEST_PROFILE_WITH_RULES.way_penalty(&tags! {}), f32::INFINITY);
+        // factor_rules multiply in on top of the base penalty, same as factors.
+        assert_eq!(
+            TEST_PROFILE_WITH_RULES
+                .way_penalty(&tags! {"highway": "residential", "smoothness": "bad"}),
+            2.0,
+        );
+        // smoothness=good does not satisfy NotEquals("smoothness", "good") - no factor.
+        assert_eq!(
+            TEST_PROFILE_WITH_RULES
+                .way_penalty(&tags! {"highway": "residential", "smoothness": "good"}),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn condition_matches() {
+        let tags = tags! {"surface": "gravel", "highway": "track"};
+        assert!(Condition::KeyPresent("surface").matches(&tags));
+        assert!(!Condition::KeyPresent("access").matches(&tags));
+        assert!(Condition::KeyAbsent("access").matches(&tags));
+        assert!(!Condition::KeyAbsent("surface").matches(&tags));
+        assert!(Condition::Equals("surface", "gravel").matches(&tags));
+        assert!(!Condition::Equals("surface", "mud").matches(&tags));
+        assert!(Condition::NotEquals("surface", "mud").matches(&tags));
+        // NotEquals does not match an absent tag, same as JOSM's `!=`.
+        assert!(!Condition::NotEquals("access", "no").matches(&tags));
+        assert!(Condition::Regex("surface", "^(unpaved|gravel|dirt)$").matches(&tags));
+        assert!(!Condition::Regex("surface", "^(unpaved|dirt)$").matches(&tags));
+        // Regex on a missing key never matches.
+        assert!(!Condition::Regex("access", "^(no|private)$").matches(&tags));
+    }
+
+    #[test]
+    fn match_value_pattern_unanchored() {
+        // Without anchors, matching is a substring/prefix/suffix search.
+        assert!(match_value_pattern("grav", "gravel"));
+        assert!(match_value_pattern("^grav", "gravel"));
+        assert!(!match_value_pattern("^grav", "fine_gravel"));
+        assert!(match_value_pattern("el$", "gravel"));
+        assert!(!match_value_pattern("el$", "elsewhere"));
+        // A single alternation group works the same way with or without anchors.
+        assert!(match_value_pattern("(unpaved|gravel)", "fine_gravel_crushed"));
+        assert!(!match_value_pattern("^(unpaved|gravel)$", "fine_gravel_crushed"));
+    }
+
+    #[test]
+    fn edge_cost_distance() {
+        // Default WeightMode::Distance is just way_penalty() * distance.
+        assert_eq!(TEST_PROFILE.edge_cost(&tags! {"highway": "path"}, 5.0), 10.0);
+        assert_eq!(
+            TEST_PROFILE.edge_cost(&tags! {"highway": "motorway"}, 5.0),
+            f32::INFINITY,
+        );
+    }
+
+    #[test]
+    fn edge_cost_duration() {
+        // Falls back to the matching Speed entry (path: 10 km/h).
+        assert_eq!(
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "path"}, 5.0),
+            0.5,
+        );
+        // Falls back further to DEFAULT_SPEED_KMH (30 km/h) with no Speed match.
+        assert_eq!(
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "footway"}, 3.0),
+            0.1,
+        );
+        // maxspeed overrides the Speed table.
+        assert_eq!(
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "path", "maxspeed": "50"}, 5.0),
+            0.1,
+        );
+        // mph/knots suffixes get converted to km/h.
+        let mph_cost =
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "path", "maxspeed": "30 mph"}, 5.0);
+        assert!((mph_cost - 5.0 / (30.0 * 1.609344)).abs() < 1e-4);
+        // Non-numeric maxspeed values (e.g. "none") are ignored, falling back as usual.
+        assert_eq!(
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "path", "maxspeed": "none"}, 5.0),
+            0.5,
+        );
+        // Still INFINITY for an unroutable way.
+        assert_eq!(
+            TEST_PROFILE_DURATION.edge_cost(&tags! {"highway": "motorway"}, 5.0),
+            f32::INFINITY,
+        );
+    }
+
+    #[test]
+    fn edge_cost_routability() {
+        // Duration multiplied by way_penalty (path: penalty 2.0, speed 10 km/h).
+        assert_eq!(
+            TEST_PROFILE_ROUTABILITY.edge_cost(&tags! {"highway": "path"}, 5.0),
+            1.0,
+        );
+    }
+
     #[test]
     fn is_allowed() {
         assert!(TEST_PROFILE.is_allowed(&tags! {"highway": "footway"}));
@@ -890,6 +2357,152 @@ mod tests {
             .is_allowed(&tags! {"highway": "footway", "motorroad": "yes"}));
     }
 
+    #[test]
+    fn access_level() {
+        assert_eq!(TEST_PROFILE.access_level(&tags! {}), Access::Allowed);
+        assert_eq!(
+            TEST_PROFILE.access_level(&tags! {"access": "no"}),
+            Access::Disallowed,
+        );
+        assert_eq!(
+            TEST_PROFILE.access_level(&tags! {"access": "destination"}),
+            Access::Allowed,
+        );
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS.access_level(&tags! {"access": "destination"}),
+            Access::Restricted,
+        );
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS.access_level(&tags! {"access": "no"}),
+            Access::Disallowed,
+        );
+        // The most specific mode wins, same hierarchy as is_allowed/node_penalty.
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS
+                .access_level(&tags! {"access": "no", "cat": "customers"}),
+            Access::Restricted,
+        );
+    }
+
+    #[test]
+    fn access_level_hierarchy() {
+        // CAR_PROFILE's access chain is access < vehicle < motor_vehicle < motorcar - the
+        // most specific present key wins, regardless of how far down the chain it sits.
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"access": "no", "motorcar": "yes"}),
+            Access::Allowed,
+        );
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"motor_vehicle": "yes", "motorcar": "no"}),
+            Access::Disallowed,
+        );
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"vehicle": "no", "motor_vehicle": "permissive"}),
+            Access::Allowed,
+        );
+        // access=agricultural/forestry reserve the way for other traffic - fully blocked,
+        // not merely discouraged like access=destination.
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"access": "agricultural"}),
+            Access::Disallowed,
+        );
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"access": "forestry"}),
+            Access::Disallowed,
+        );
+        // Values outside both lists - e.g. the common "designated" - stay Allowed.
+        assert_eq!(
+            CAR_PROFILE.access_level(&tags! {"access": "designated"}),
+            Access::Allowed,
+        );
+    }
+
+    #[test]
+    fn way_penalty_with_restricted_access() {
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS.way_penalty(&tags! {"highway": "path"}),
+            2.0,
+        );
+        // Restricted access multiplies the penalty instead of blocking routing outright.
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS
+                .way_penalty(&tags! {"highway": "path", "access": "destination"}),
+            10.0,
+        );
+        // Disallowed access still returns INFINITY.
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS
+                .way_penalty(&tags! {"highway": "path", "access": "no"}),
+            f32::INFINITY,
+        );
+    }
+
+    #[test]
+    fn is_allowed_vehicle_dimensions() {
+        // No dimension tags at all - passable.
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path"}));
+        // A sufficient limit is passable; an insufficient one is not.
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxheight": "4.5"}));
+        assert!(!TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxheight": "3.5"}));
+        // The "m"/"t" suffixes are stripped before parsing.
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxwidth": "2.5 m"}));
+        assert!(!TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxweight": "7 t"}));
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxweight": "7.5t"}));
+        // "default"/"none"/"signed" and malformed values carry no known numeric limit.
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxlength": "default"}));
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxweight": "signed"}));
+        assert!(TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "maxheight": "huh"}));
+        // hgv=no blocks goods vehicles outright, regardless of dimensions.
+        assert!(!TEST_PROFILE_TRUCK.is_allowed(&tags! {"highway": "path", "hgv": "no"}));
+        // ... but not profiles without any configured vehicle dimension.
+        assert!(TEST_PROFILE.is_allowed(&tags! {"highway": "path", "hgv": "no"}));
+        // maxweight:conditional (time-dependent) is ignored, only the plain tag matters.
+        assert!(TEST_PROFILE_TRUCK
+            .is_allowed(&tags! {"highway": "path", "maxweight:conditional": "3.5 @ (wet)"}));
+    }
+
+    #[test]
+    fn node_penalty() {
+        assert_eq!(TEST_PROFILE.node_penalty(&tags! {}), 1.0);
+        assert_eq!(TEST_PROFILE.node_penalty(&tags! {"barrier": "gate"}), f32::INFINITY);
+        assert_eq!(TEST_PROFILE.node_penalty(&tags! {"barrier": "bollard"}), 1.0);
+        assert_eq!(
+            TEST_PROFILE.node_penalty(&tags! {"barrier": "kissing_gate"}),
+            1.5,
+        );
+        assert_eq!(
+            TEST_PROFILE.node_penalty(&tags! {"barrier": "entrance"}),
+            1.0,
+        );
+        // A node's own access tags override the barrier table, same hierarchy as is_allowed.
+        assert_eq!(
+            TEST_PROFILE.node_penalty(&tags! {"barrier": "gate", "cat": "yes"}),
+            1.0,
+        );
+        assert_eq!(
+            TEST_PROFILE.node_penalty(&tags! {"barrier": "bollard", "access": "no"}),
+            f32::INFINITY,
+        );
+        // A Restricted access value overrides the barrier table with restricted_access_penalty.
+        assert_eq!(
+            TEST_PROFILE_WITH_RESTRICTED_ACCESS
+                .node_penalty(&tags! {"barrier": "gate", "access": "destination"}),
+            5.0,
+        );
+    }
+
+    #[test]
+    fn node_is_allowed() {
+        assert!(TEST_PROFILE.node_is_allowed(&tags! {}));
+        assert!(!TEST_PROFILE.node_is_allowed(&tags! {"barrier": "gate"}));
+        assert!(TEST_PROFILE.node_is_allowed(&tags! {"barrier": "bollard"}));
+        // An access override still wins over a blocking barrier, same as node_penalty.
+        assert!(TEST_PROFILE.node_is_allowed(&tags! {"barrier": "gate", "cat": "yes"}));
+        // A Restricted access value is finite, so it still counts as allowed here.
+        assert!(TEST_PROFILE_WITH_RESTRICTED_ACCESS
+            .node_is_allowed(&tags! {"barrier": "gate", "access": "destination"}));
+    }
+
     #[test]
     fn way_direction() {
         assert_eq!(
@@ -992,6 +2605,16 @@ mod tests {
                 .restriction_kind(&tags! {"type": "restriction", "restriction:cat": "no_u_turn"}),
             TurnRestriction::Prohibitory,
         );
+        assert_eq!(
+            TEST_PROFILE
+                .restriction_kind(&tags! {"type": "restriction", "restriction": "no_entry"}),
+            TurnRestriction::NoEntry,
+        );
+        assert_eq!(
+            TEST_PROFILE
+                .restriction_kind(&tags! {"type": "restriction", "restriction": "no_exit"}),
+            TurnRestriction::NoExit,
+        );
     }
 
     #[test]
@@ -1008,6 +2631,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn way_penalty_foot_surface_grades() {
+        let paved = FOOT_PROFILE.way_penalty(&tags! {"highway": "path"});
+        // A surface value not listed in the factors (here asphalt) leaves the cost unchanged.
+        assert_eq!(
+            FOOT_PROFILE.way_penalty(&tags! {"highway": "path", "surface": "asphalt"}),
+            paved,
+        );
+        // An unpaved surface raises cost, and mud raises it further still.
+        let unpaved = FOOT_PROFILE.way_penalty(&tags! {"highway": "path", "surface": "unpaved"});
+        let mud = FOOT_PROFILE.way_penalty(&tags! {"highway": "path", "surface": "mud"});
+        assert!(unpaved > paved);
+        assert!(mud > unpaved);
+        // Smoothness and tracktype grades stack the same way, multiplicatively.
+        assert_eq!(
+            FOOT_PROFILE.way_penalty(&tags! {"highway": "path", "smoothness": "very_horrible"}),
+            paved * 4.0,
+        );
+        assert_eq!(
+            FOOT_PROFILE.way_penalty(&tags! {"highway": "track", "tracktype": "grade5"}),
+            FOOT_PROFILE.way_penalty(&tags! {"highway": "track"}) * 1.3,
+        );
+    }
+
+    #[test]
+    fn way_penalty_bicycle_surface_grades() {
+        let paved = BICYCLE_PROFILE.way_penalty(&tags! {"highway": "track"});
+        assert_eq!(
+            BICYCLE_PROFILE.way_penalty(&tags! {"highway": "track", "surface": "asphalt"}),
+            paved,
+        );
+        let gravel = BICYCLE_PROFILE.way_penalty(&tags! {"highway": "track", "surface": "gravel"});
+        let mud = BICYCLE_PROFILE.way_penalty(&tags! {"highway": "track", "surface": "mud"});
+        assert!(gravel > paved);
+        assert!(mud > gravel);
+        // Independent graded tags (surface, smoothness, tracktype) multiply together.
+        assert_eq!(
+            BICYCLE_PROFILE.way_penalty(
+                &tags! {"highway": "track", "surface": "mud", "tracktype": "grade5"}
+            ),
+            paved * 8.0 * 4.0,
+        );
+    }
+
+    #[test]
+    fn turn_cost() {
+        // Straight ahead: no turn, no penalty.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 0.0, &tags! {}), 0.0);
+        // A 90-degree right turn (heading increases): turn_penalty * (90/180), bias 1.0.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 90.0, &tags! {}), 0.5);
+        // A 90-degree left turn (heading decreases): turn_penalty * (90/180) * turn_bias.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 270.0, &tags! {}), 1.0);
+        // Headings wrap around 360 the same way.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(350.0, 80.0, &tags! {}), 0.5);
+        // A u-turn (180 degrees) snaps to the full u_turn_penalty, ignoring turn_penalty.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 180.0, &tags! {}), 10.0);
+        // A 160-degree turn is already past U_TURN_ANGLE_DEG.
+        assert_eq!(TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 160.0, &tags! {}), 10.0);
+        // Traffic signals add an extra additive cost, on top of the turn cost.
+        assert_eq!(
+            TEST_PROFILE_WITH_TURN_COSTS
+                .turn_cost(0.0, 0.0, &tags! {"highway": "traffic_signals"}),
+            0.5,
+        );
+        assert_eq!(
+            TEST_PROFILE_WITH_TURN_COSTS.turn_cost(0.0, 90.0, &tags! {"highway": "stop"}),
+            1.0,
+        );
+    }
+
     #[test]
     fn is_exempted() {
         assert!(!TEST_PROFILE.is_exempted(&tags! {}));
@@ -1015,4 +2708,175 @@ mod tests {
         assert!(TEST_PROFILE.is_exempted(&tags! {"except": "cat"}));
         assert!(TEST_PROFILE.is_exempted(&tags! {"except": "psv;cat"}));
     }
+
+    #[test]
+    fn conditional_access() {
+        let conditional_tags = tags! {
+            "highway": "path",
+            "access": "yes",
+            "access:conditional": "no @ (Mo-Fr 07:00-19:00); no @ (PH)"
+        };
+        // Without query_time set, the conditional tag is ignored entirely.
+        assert_eq!(TEST_PROFILE.access_level(&conditional_tags), Access::Allowed);
+        // Wednesday 10:00 falls inside the matching clause's weekday+time range.
+        assert_eq!(TEST_PROFILE_AT_TIME.access_level(&conditional_tags), Access::Disallowed);
+        // Outside both clauses, the conditional tag doesn't match, so the plain tag wins.
+        let evening = Profile {
+            query_time: Some(OpeningHoursInstant {
+                weekday: Weekday::Wed,
+                month: Month::Jun,
+                hour: 20,
+                minute: 0,
+                is_public_holiday: false,
+            }),
+            ..TEST_PROFILE_AT_TIME
+        };
+        assert_eq!(evening.access_level(&conditional_tags), Access::Allowed);
+        // A public holiday matches the second clause regardless of weekday/time.
+        let holiday = Profile {
+            query_time: Some(OpeningHoursInstant {
+                weekday: Weekday::Sun,
+                month: Month::Jun,
+                hour: 20,
+                minute: 0,
+                is_public_holiday: true,
+            }),
+            ..TEST_PROFILE_AT_TIME
+        };
+        assert_eq!(holiday.access_level(&conditional_tags), Access::Disallowed);
+    }
+
+    #[test]
+    fn conditional_access_last_match_and_months() {
+        // Later clauses override earlier ones, same as opening_hours - the
+        // season-wide "no" is narrowed back to "yes" by the later, more specific clause.
+        let tags = tags! {
+            "highway": "path",
+            "access": "yes",
+            "access:conditional": "no @ (Jun-Aug); yes @ (Jul 12:00-14:00)"
+        };
+        let summer_midday = Profile {
+            query_time: Some(OpeningHoursInstant {
+                weekday: Weekday::Wed,
+                month: Month::Jul,
+                hour: 13,
+                minute: 0,
+                is_public_holiday: false,
+            }),
+            ..TEST_PROFILE_AT_TIME
+        };
+        assert_eq!(summer_midday.access_level(&tags), Access::Allowed);
+        let summer_evening = Profile {
+            query_time: Some(OpeningHoursInstant {
+                weekday: Weekday::Wed,
+                month: Month::Jul,
+                hour: 20,
+                minute: 0,
+                is_public_holiday: false,
+            }),
+            ..TEST_PROFILE_AT_TIME
+        };
+        assert_eq!(summer_evening.access_level(&tags), Access::Disallowed);
+        let winter = Profile {
+            query_time: Some(OpeningHoursInstant {
+                weekday: Weekday::Wed,
+                month: Month::Jan,
+                hour: 13,
+                minute: 0,
+                is_public_holiday: false,
+            }),
+            ..TEST_PROFILE_AT_TIME
+        };
+        assert_eq!(winter.access_level(&tags), Access::Allowed);
+    }
+
+    #[test]
+    fn conditional_oneway() {
+        let tags = tags! {
+            "highway": "path",
+            "oneway": "no",
+            "oneway:conditional": "yes @ (Mo-Fr 07:00-19:00)"
+        };
+        assert_eq!(TEST_PROFILE.way_direction(&tags), (true, true));
+        assert_eq!(TEST_PROFILE_AT_TIME.way_direction(&tags), (true, false));
+    }
+
+    #[test]
+    fn conditional_restriction() {
+        let tags = tags! {
+            "type": "restriction",
+            "restriction": "no_u_turn",
+            "restriction:conditional": "only_left_turn @ (Mo-Fr 07:00-19:00)"
+        };
+        assert_eq!(TEST_PROFILE.restriction_kind(&tags), TurnRestriction::Prohibitory);
+        assert_eq!(
+            TEST_PROFILE_AT_TIME.restriction_kind(&tags),
+            TurnRestriction::Mandatory,
+        );
+    }
+
+    #[test]
+    fn evaluate_conditional_weekday_wrap() {
+        // A weekday range wrapping past Sunday, e.g. "Fr-Mo", covers Fri, Sat, Sun, Mon.
+        let at = |weekday| OpeningHoursInstant {
+            weekday,
+            month: Month::Jun,
+            hour: 12,
+            minute: 0,
+            is_public_holiday: false,
+        };
+        assert_eq!(Profile::evaluate_conditional("no @ (Fr-Mo)", Some(at(Weekday::Sat))), Some("no"));
+        assert_eq!(Profile::evaluate_conditional("no @ (Fr-Mo)", Some(at(Weekday::Wed))), None);
+    }
+
+    #[test]
+    fn evaluate_conditional_month_wrap() {
+        // A month range wrapping past the year boundary, e.g. "Nov-Feb", covers Nov..Feb.
+        let at = |month| OpeningHoursInstant {
+            weekday: Weekday::Mon,
+            month,
+            hour: 12,
+            minute: 0,
+            is_public_holiday: false,
+        };
+        assert_eq!(Profile::evaluate_conditional("no @ (Nov-Feb)", Some(at(Month::Jan))), Some("no"));
+        assert_eq!(Profile::evaluate_conditional("no @ (Nov-Feb)", Some(at(Month::Jun))), None);
+    }
+
+    #[test]
+    fn evaluate_conditional_time_wrap() {
+        // A time range wrapping past midnight, e.g. "22:00-06:00".
+        let at = |hour, minute| OpeningHoursInstant {
+            weekday: Weekday::Mon,
+            month: Month::Jun,
+            hour,
+            minute,
+            is_public_holiday: false,
+        };
+        assert_eq!(
+            Profile::evaluate_conditional("no @ (22:00-06:00)", Some(at(23, 0))),
+            Some("no"),
+        );
+        assert_eq!(
+            Profile::evaluate_conditional("no @ (22:00-06:00)", Some(at(5, 0))),
+            Some("no"),
+        );
+        assert_eq!(Profile::evaluate_conditional("no @ (22:00-06:00)", Some(at(12, 0))), None);
+    }
+
+    #[test]
+    fn evaluate_conditional_time_list() {
+        // A comma-separated list of time ranges, e.g. rush-hour restrictions.
+        let at = |hour, minute| OpeningHoursInstant {
+            weekday: Weekday::Mon,
+            month: Month::Jun,
+            hour,
+            minute,
+            is_public_holiday: false,
+        };
+        let tag = "no @ (Mo-Fr 06:00-09:00,16:00-19:00)";
+        assert_eq!(Profile::evaluate_conditional(tag, Some(at(7, 0))), Some("no"));
+        assert_eq!(Profile::evaluate_conditional(tag, Some(at(17, 30))), Some("no"));
+        assert_eq!(Profile::evaluate_conditional(tag, Some(at(12, 0))), None);
+    }
 }
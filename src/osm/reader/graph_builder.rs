@@ -1,8 +1,9 @@
 // (c) Copyright 2025 Mikołaj Kuranowski
 // SPDX-License-Identifier: MIT
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, TryReserveError};
 
+use crate::distance::earth_bearing;
 use crate::osm::profile::TurnRestriction;
 use crate::osm::reader::FeatureReader;
 use crate::{earth_distance, Edge, Graph, Node};
@@ -14,18 +15,57 @@ const MAX_NODE_ID: i64 = 0x0008_0000_0000_0000;
 
 /// Helper object used for storing state related to converting [OSM features](super::model::Feature)
 /// into a [Graph].
-pub(super) struct GraphBuilder<'a> {
+///
+/// Turn restriction `relation`s (`from`/`via`/`to` members) are honored by cloning the
+/// `via` node(s) into phantoms and rewiring edges around them - see
+/// [GraphBuilder::store_restriction]. The result is still a plain node-based [Graph], so
+/// [find_route](crate::find_route) and [find_route_without_turn_around](crate::find_route_without_turn_around)
+/// need no awareness of restrictions at all; the phantom nodes enforce them implicitly.
+///
+/// A single [GraphBuilder] can ingest several batches of features by calling
+/// [GraphBuilder::add_features] repeatedly - `way_nodes` is kept between calls, so a
+/// restriction relation in a later batch can still resolve `from`/`via`/`to` ways
+/// loaded by an earlier one. Each batch must still be self-contained: a way may only
+/// reference nodes present in that same batch or already added to the [Graph].
+/// Merging is deterministic: for a node id seen more than once, the first (already
+/// existing) node wins; for an edge between the same two nodes, the most recently
+/// added one overwrites the old cost. Call [GraphBuilder::finish] once after the last
+/// batch to drop nodes that ended up referenced by no way.
+pub struct GraphBuilder<'a> {
     g: &'a mut Graph,
     options: &'a Options<'a>,
     phantom_node_id_counter: i64,
+    /// Ids of phantom nodes freed by [GraphBuilder::remove_relation], popped by the private
+    /// `GraphChange::clone_node` before it falls back to bumping `phantom_node_id_counter`, so
+    /// repeated relation add/remove cycles don't fragment the id space.
+    phantom_free_list: Vec<i64>,
     unused_nodes: HashSet<i64>,
     way_nodes: HashMap<i64, Vec<i64>>,
+    relation_changes: HashMap<i64, RelationChange>,
+    /// Tags of nodes carrying any, keyed by node id, consulted by [GraphBuilder::create_edges]
+    /// to apply [Profile::node_penalty](crate::osm::Profile::node_penalty) around that node,
+    /// and by [GraphBuilder::apply_turn_costs] to pass to
+    /// [Profile::turn_cost](crate::osm::Profile::turn_cost). See [super::model::Feature::Node].
+    node_tags: HashMap<i64, HashMap<String, String>>,
     ignore_bbox: bool,
 }
 
+/// Error returned by [GraphBuilder::try_add_features].
+#[derive(Debug, thiserror::Error)]
+pub enum TryAddFeaturesError<E: std::error::Error> {
+    /// An internal bookkeeping map/vector failed to grow - the caller may want to retry
+    /// with a tighter [bbox](Options::bbox) rather than aborting the whole program.
+    #[error("out of memory: {0}")]
+    OutOfMemory(#[from] TryReserveError),
+
+    /// The underlying [FeatureReader] failed.
+    #[error(transparent)]
+    Reader(E),
+}
+
 impl<'a> GraphBuilder<'a> {
     /// Create a new, empty graph builder.
-    pub(super) fn new(g: &'a mut Graph, options: &'a Options<'a>) -> Self {
+    pub fn new(g: &'a mut Graph, options: &'a Options<'a>) -> Self {
         // Start adding phantom nodes at MAX_NODE_ID,
         // or the max node ID from the graph (in case phantom nodes were already added).
         let phantom_node_id_counter =
@@ -37,57 +77,431 @@ impl<'a> GraphBuilder<'a> {
             g,
             options,
             phantom_node_id_counter,
+            phantom_free_list: Vec::default(),
             unused_nodes: HashSet::default(),
             way_nodes: HashMap::default(),
+            relation_changes: HashMap::default(),
+            node_tags: HashMap::default(),
             ignore_bbox,
         }
     }
 
-    /// Add all features from the provided [FeatureReader].
-    pub(super) fn add_features<F: FeatureReader>(&mut self, features: F) -> Result<(), F::Error> {
+    /// Add one batch of features from the provided [FeatureReader].
+    ///
+    /// May be called multiple times on the same [GraphBuilder] to merge several
+    /// batches into one [Graph] - see the type-level docs for the merge semantics.
+    /// Call [GraphBuilder::finish] once after the last batch.
+    ///
+    /// Panics if the node/way bookkeeping maps fail to grow - see
+    /// [GraphBuilder::try_add_features] for a fallible equivalent that reports such
+    /// allocation failures instead of aborting the process, suitable for ingesting
+    /// planet-scale extracts under a memory-constrained cgroup/container.
+    pub fn add_features<F: FeatureReader>(&mut self, features: F) -> Result<(), F::Error> {
+        match self.try_add_features(features) {
+            Ok(()) => Ok(()),
+            Err(TryAddFeaturesError::OutOfMemory(e)) => {
+                panic!("out of memory while building graph: {e}")
+            }
+            Err(TryAddFeaturesError::Reader(e)) => Err(e),
+        }
+    }
+
+    /// Same as [GraphBuilder::add_features], but grows the node/way bookkeeping maps with
+    /// `try_reserve`/`try_reserve_exact` and reports an allocation failure as
+    /// [TryAddFeaturesError::OutOfMemory] instead of aborting the process. This lets a caller
+    /// fail gracefully - and retry with a tighter [bbox](Options::bbox) - rather than having
+    /// the whole program killed by the allocator.
+    pub fn try_add_features<F: FeatureReader>(
+        &mut self,
+        features: F,
+    ) -> Result<(), TryAddFeaturesError<F::Error>> {
         for f in features {
-            self.add_feature(f?);
+            self.try_add_feature(f.map_err(TryAddFeaturesError::Reader)?)?;
         }
-        self.cleanup();
         Ok(())
     }
 
-    fn cleanup(&mut self) {
+    /// Finalizes the graph, dropping any node that, across every ingested batch,
+    /// ended up referenced by no way.
+    ///
+    /// This only drops nodes that never belonged to any way; it does not prune
+    /// strongly-connected-component islands that are technically wired up but unreachable from
+    /// the rest of the graph (digitizing errors, bbox-clipped stubs) - call
+    /// [GraphBuilder::prune_disconnected] explicitly for that, since it needs a `min_size`/`seed`
+    /// judgement call that `finish` has no reasonable default for.
+    pub fn finish(mut self) {
+        self.apply_turn_costs();
         self.unused_nodes.iter().for_each(|&id| {
             self.g.delete_node(id);
         });
     }
 
-    fn add_feature(&mut self, f: model::Feature) {
+    /// Folds [Profile::turn_cost](crate::osm::Profile::turn_cost) into every edge added so
+    /// far.
+    ///
+    /// A node touching at most 2 distinct neighbors (the overwhelming majority - any
+    /// interior node of a way that isn't itself an intersection) has no real choice of
+    /// incoming direction: for each outgoing edge, the other neighbor is the only possible
+    /// predecessor, so the cost is added directly onto that edge, in place.
+    ///
+    /// A node touching 3 or more distinct neighbors is a genuine branch/merge junction,
+    /// where the right turn cost depends on which neighbor was actually arrived from. That
+    /// can't be encoded on a single outgoing edge, so all but one predecessor are redirected
+    /// into a clone of their own instead - the same phantom-node trick
+    /// [Self::store_restriction] uses for hard restrictions. The remaining predecessor is
+    /// deliberately left un-redirected and keeps the junction's own canonical id, so the
+    /// junction stays usable as a route start/end point, which matters since
+    /// [SpatialIndex](crate::spatial::SpatialIndex) only ever resolves a coordinate to
+    /// canonical ids, never to a phantom clone.
+    ///
+    /// Every node's predecessor-to-clone assignment is decided up front, from a single
+    /// immutable snapshot of the OSM-derived topology, before any edge's turn-adjusted cost
+    /// is computed - so when one junction's outgoing edge leads straight into another
+    /// junction (no plain node in between), the edge is written directly to the correct
+    /// clone (or canonical node) on the far side, regardless of which of the two junctions
+    /// happens to be visited first.
+    ///
+    /// Does nothing if [Profile::u_turn_penalty](crate::osm::Profile::u_turn_penalty),
+    /// [Profile::turn_penalty](crate::osm::Profile::turn_penalty) and
+    /// [Profile::traffic_signal_penalty](crate::osm::Profile::traffic_signal_penalty) are all
+    /// zero, which is the default/no-op configuration.
+    fn apply_turn_costs(&mut self) {
+        let profile = self.options.profile;
+        if profile.u_turn_penalty == 0.0
+            && profile.turn_penalty == 0.0
+            && profile.traffic_signal_penalty == 0.0
+        {
+            return;
+        }
+
+        // Snapshot the OSM-derived topology (both predecessors and outgoing edges) up
+        // front - nothing below ever reads back an edge this function itself has written,
+        // so it can't matter which order nodes happen to be visited in.
+        let mut predecessors: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut outgoing: HashMap<i64, Vec<Edge>> = HashMap::new();
+        for node in self.g.iter() {
+            let edges = self.g.get_edges(node.id).to_vec();
+            for edge in &edges {
+                predecessors.entry(edge.to).or_default().push(node.id);
+            }
+            outgoing.insert(node.id, edges);
+        }
+
+        let mut via_ids: Vec<i64> = outgoing.keys().copied().collect();
+        via_ids.sort_unstable();
+
+        let distinct_preds = |via_id: i64| -> Vec<i64> {
+            let mut preds = predecessors.get(&via_id).cloned().unwrap_or_default();
+            preds.sort_unstable();
+            preds.dedup();
+            preds
+        };
+        let distinct_neighbors = |preds: &[i64], via_edges: &[Edge]| -> Vec<i64> {
+            let mut neighbors = preds.to_vec();
+            neighbors.extend(via_edges.iter().map(|e| e.to));
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        };
+        let is_junction = |preds: &[i64], via_edges: &[Edge]| -> bool {
+            !preds.is_empty() && !via_edges.is_empty() && distinct_neighbors(preds, via_edges).len() > 2
+        };
+
+        // Step 1: decide, for every node and every one of its predecessors, which node
+        // represents "at this node, having just arrived via that predecessor" - itself for
+        // a node with at most 2 distinct neighbors (no real choice of incoming direction,
+        // so no need to tell arrivals apart) or for a junction's kept predecessor (the
+        // lowest-numbered one, arbitrarily but deterministically); a fresh phantom clone for
+        // every other predecessor of a junction. This is a pure, order-independent decision
+        // - it only assigns ids, it never computes a cost or looks at another node's
+        // decision - so step 2 can resolve any edge's destination via this map regardless of
+        // whether that destination has already been visited.
+        let mut node_for: HashMap<(i64, i64), i64> = HashMap::new();
+        for &via_id in &via_ids {
+            let via_edges = &outgoing[&via_id];
+            let preds = distinct_preds(via_id);
+            if !is_junction(&preds, via_edges) {
+                for &from_id in &preds {
+                    node_for.insert((from_id, via_id), via_id);
+                }
+                continue;
+            }
+
+            let via_node = self.g.get_node(via_id).expect("node came from self.g.iter()");
+            let kept_pred = preds[0];
+            for &from_id in &preds {
+                if from_id == kept_pred {
+                    node_for.insert((from_id, via_id), via_id);
+                } else {
+                    let clone_id = self.phantom_free_list.pop().unwrap_or_else(|| {
+                        self.phantom_node_id_counter += 1;
+                        self.phantom_node_id_counter
+                    });
+                    self.g.set_node(Node {
+                        id: clone_id,
+                        osm_id: via_node.osm_id,
+                        lat: via_node.lat,
+                        lon: via_node.lon,
+                    });
+                    node_for.insert((from_id, via_id), clone_id);
+                }
+            }
+        }
+
+        // Step 2: with every node's arrival-identity fully decided above, compute each
+        // edge's turn-adjusted cost and write it from the correct arrived-via-predecessor
+        // source to the correct arrived-via-predecessor destination.
+        for &via_id in &via_ids {
+            let via_edges = &outgoing[&via_id];
+            let preds = distinct_preds(via_id);
+            let via_node = self.g.get_node(via_id).expect("node came from self.g.iter()");
+            // Tags are recorded under the OSM id, not `via_id` - which may be a phantom node
+            // cloned by an earlier turn restriction over the same junction.
+            let via_tags = self.node_tags.get(&via_node.osm_id).cloned().unwrap_or_default();
+
+            if is_junction(&preds, via_edges) {
+                // A genuine branch/merge junction: every predecessor sees every outgoing
+                // edge, each from its own arrival-specific source node.
+                for from_id in preds {
+                    let Some(from_node) = self.g.get_node(from_id) else {
+                        continue;
+                    };
+                    let from_heading =
+                        earth_bearing(from_node.lat, from_node.lon, via_node.lat, via_node.lon);
+                    let source = node_for[&(from_id, via_id)];
+
+                    for edge in via_edges {
+                        let Some(to_node) = self.g.get_node(edge.to) else {
+                            continue;
+                        };
+                        let to_heading =
+                            earth_bearing(via_node.lat, via_node.lon, to_node.lat, to_node.lon);
+                        let cost =
+                            edge.cost + profile.turn_cost(from_heading, to_heading, &via_tags);
+                        let target = node_for.get(&(via_id, edge.to)).copied().unwrap_or(edge.to);
+                        if source == via_id && target != edge.to {
+                            self.g.delete_edge(via_id, edge.to);
+                        }
+                        self.g.set_edge(source, Edge { to: target, cost });
+                    }
+                }
+            } else {
+                // No real choice of incoming direction: for each outgoing edge, the other
+                // neighbor is the only possible predecessor - except at a dead end with a
+                // single neighbor used both ways, where that "other" predecessor doesn't
+                // exist and the only honest arrival direction is the sole neighbor itself,
+                // i.e. a U-turn.
+                for edge in via_edges {
+                    let from_id = match preds.iter().find(|&&n| n != edge.to) {
+                        Some(&n) => n,
+                        None if preds.len() == 1 => preds[0],
+                        None => continue,
+                    };
+                    let Some(from_node) = self.g.get_node(from_id) else {
+                        continue;
+                    };
+                    let Some(to_node) = self.g.get_node(edge.to) else {
+                        continue;
+                    };
+                    let from_heading =
+                        earth_bearing(from_node.lat, from_node.lon, via_node.lat, via_node.lon);
+                    let to_heading =
+                        earth_bearing(via_node.lat, via_node.lon, to_node.lat, to_node.lon);
+                    let cost = edge.cost + profile.turn_cost(from_heading, to_heading, &via_tags);
+                    let target = node_for.get(&(via_id, edge.to)).copied().unwrap_or(edge.to);
+                    if target != edge.to {
+                        self.g.delete_edge(via_id, edge.to);
+                    }
+                    self.g.set_edge(via_id, Edge { to: target, cost });
+                }
+            }
+        }
+    }
+
+    /// Computes [strongly connected components](Graph::compute_components) of the graph built
+    /// so far and deletes every node belonging to a component smaller than `min_size`, or -
+    /// if `seed` is provided - not in the component reachable from `seed`. This discards tiny
+    /// disconnected islands left by OSM import artifacts (ferry stubs, mis-tagged service
+    /// loops) that would otherwise silently make routing to/from them fail.
+    ///
+    /// A phantom node created by turn-restriction expansion (see
+    /// [GraphBuilder::store_restriction]) is only pruned if the canonical node sharing its
+    /// `osm_id` is pruned too, so a restriction's rewiring is never left half-expanded.
+    ///
+    /// Returns the ids of every node that was removed.
+    pub fn prune_disconnected(&mut self, min_size: usize, seed: Option<i64>) -> Vec<i64> {
+        self.g.compute_components();
+
+        let mut component_size: HashMap<u32, usize> = HashMap::new();
+        for node in self.g.iter() {
+            if node.id == node.osm_id {
+                if let Some(component) = self.g.component_id(node.id) {
+                    *component_size.entry(component).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let seed_component = seed.and_then(|s| self.g.component_id(s));
+
+        let to_remove: Vec<i64> = self
+            .g
+            .iter()
+            .filter_map(|node| {
+                // Phantom nodes are judged by their canonical counterpart's component,
+                // not their own - they must follow it whichever way it goes.
+                let canonical_id = if node.id == node.osm_id { node.id } else { node.osm_id };
+                let component = self.g.component_id(canonical_id)?;
+
+                let big_enough = component_size.get(&component).copied().unwrap_or(0) >= min_size;
+                let reachable_from_seed = seed_component.map_or(true, |s| s == component);
+
+                (!(big_enough && reachable_from_seed)).then_some(node.id)
+            })
+            .collect();
+
+        for &id in &to_remove {
+            self.g.delete_node(id);
+        }
+
+        to_remove
+    }
+
+    /// Removes the edges a previously-ingested way introduced, e.g. after a live OSM diff
+    /// deletes it. Nodes that become referenced by no other ingested way are returned to
+    /// [GraphBuilder::unused_nodes], same as if the way had never been added - call
+    /// [GraphBuilder::finish] afterwards to drop them. Does nothing if `way_id` wasn't
+    /// ingested (or was already removed).
+    pub fn remove_way(&mut self, way_id: i64) {
+        let Some(nodes) = self.way_nodes.remove(&way_id) else {
+            return;
+        };
+
+        for pair in nodes.windows(2) {
+            self.g.delete_edge(pair[0], pair[1]);
+            self.g.delete_edge(pair[1], pair[0]);
+        }
+
+        for &node_id in &nodes {
+            if !self.way_nodes.values().any(|ns| ns.contains(&node_id)) {
+                self.unused_nodes.insert(node_id);
+            }
+        }
+    }
+
+    /// Removes the turn restriction introduced by a previously-ingested `relation_id`:
+    /// deletes every phantom node it created, restores every edge it removed (with its
+    /// original cost), and deletes every edge it added - the exact inverse of
+    /// [GraphBuilder::store_restriction]. Freed phantom node ids are pushed onto a free list
+    /// so a later restriction reuses them instead of growing `phantom_node_id_counter`
+    /// forever. Does nothing if `relation_id` wasn't ingested as a turn restriction (or was
+    /// already removed).
+    pub fn remove_relation(&mut self, relation_id: i64) {
+        let Some(change) = self.relation_changes.remove(&relation_id) else {
+            return;
+        };
+
+        for (from, to) in change.added_edges {
+            self.g.delete_edge(from, to);
+        }
+        for (from, to, cost) in change.removed_edges {
+            self.g.set_edge(from, Edge { to, cost });
+        }
+        for node_id in change.phantom_nodes {
+            self.g.delete_node(node_id);
+            self.phantom_free_list.push(node_id);
+        }
+    }
+
+    /// Stages a *soft* turn restriction over the node sequence `nodes` (same shape as the
+    /// `nodes` passed to [GraphBuilder::store_restriction]: the `from`/`via`/`to` OSM node ids,
+    /// in order). Every `via` node is cloned into a phantom exactly as for a hard restriction,
+    /// but the final cloned edge is kept and given `penalty` added on top of its original cost
+    /// instead of being deleted - so both the restricted and the unrestricted movement stay
+    /// routable, and the router only takes the penalized one when it's globally cheapest.
+    ///
+    /// Useful for a caller-supplied cost model (e.g. "awkward" turns, crossing oncoming
+    /// traffic) that wants to discourage rather than outright ban a movement, unlike OSM's
+    /// `no_*`/`only_*` restriction relations, which [GraphBuilder::store_restriction] always
+    /// applies as hard bans.
+    ///
+    /// `relation_id`'s bookkeeping is merged the same way as [GraphBuilder::store_restriction],
+    /// so [GraphBuilder::remove_relation] undoes this exactly like any other restriction.
+    /// Does nothing if `nodes` is a disjoint sequence.
+    pub fn store_turn_penalty(&mut self, relation_id: i64, nodes: &[i64], penalty: f32) {
+        let mut change = GraphChange::new(self);
+        let Some(cloned_nodes) = change.restriction_as_cloned_nodes(self.g, nodes) else {
+            return; // disjoint sequence - discard
+        };
+
+        let a = cloned_nodes[cloned_nodes.len() - 2];
+        let b = cloned_nodes[cloned_nodes.len() - 1];
+        change.penalize_edge(self.g, a, b, penalty);
+
+        let relation_change = change.as_relation_change(self.g);
+        change.apply(self);
+        self.relation_changes
+            .entry(relation_id)
+            .or_default()
+            .merge(relation_change);
+    }
+
+    fn try_add_feature(&mut self, f: model::Feature) -> Result<(), TryReserveError> {
         match f {
-            model::Feature::Node(n) => self.add_node(n),
-            model::Feature::Way(w) => self.add_way(w),
-            model::Feature::Relation(r) => self.add_relation(r),
+            model::Feature::Node(n, tags, _) => self.try_add_node_with_tags(n, tags),
+            model::Feature::Way(w) => self.try_add_way(w),
+            model::Feature::Relation(r) => {
+                self.add_relation(r);
+                Ok(())
+            }
         }
     }
 
+    /// Adds a node and, if it carries any tags, remembers them in [Self::node_tags] for
+    /// [GraphBuilder::create_edges] to apply [Profile::node_penalty](crate::osm::Profile::node_penalty)
+    /// around it later.
+    fn try_add_node_with_tags(
+        &mut self,
+        n: Node,
+        tags: HashMap<String, String>,
+    ) -> Result<(), TryReserveError> {
+        let id = n.id;
+        self.try_add_node(n)?;
+        if !tags.is_empty() {
+            self.node_tags.try_reserve(1)?;
+            self.node_tags.insert(id, tags);
+        }
+        Ok(())
+    }
+
     fn add_node(&mut self, n: Node) {
+        self.try_add_node(n)
+            .expect("out of memory while building graph")
+    }
+
+    fn try_add_node(&mut self, n: Node) -> Result<(), TryReserveError> {
         debug_assert_eq!(n.id, n.osm_id);
 
         // Node already exists - ignore
         if self.g.get_node(n.id).is_some() {
-            return;
+            return Ok(());
         }
 
         // Node id invalid - ignore & warn
         if !Self::is_valid_node_id(n.id) {
             log::warn!(target: "routex.osm", "node with invalid id {} - ignoring", n.id);
-            return;
+            return Ok(());
         }
 
         // Node outside of bbox - ignore
         if !self.is_in_bbox(n.lat, n.lon) {
-            return;
+            return Ok(());
         }
 
         // Save node
+        self.unused_nodes.try_reserve(1)?;
         self.g.set_node(n);
         self.unused_nodes.insert(n.id);
+        Ok(())
     }
 
     fn is_valid_node_id(id: i64) -> bool {
@@ -103,20 +517,25 @@ impl<'a> GraphBuilder<'a> {
     }
 
     fn add_way(&mut self, w: model::Way) {
+        self.try_add_way(w)
+            .expect("out of memory while building graph")
+    }
+
+    fn try_add_way(&mut self, w: model::Way) -> Result<(), TryReserveError> {
         let penalty = self.get_way_penalty(&w);
         if penalty.is_infinite() {
-            return;
+            return Ok(());
         }
 
-        let nodes = self.get_way_nodes(&w);
+        let nodes = self.try_get_way_nodes(&w)?;
         if nodes.is_empty() {
-            return;
+            return Ok(());
         }
 
         let (forward, backward) = self.options.profile.way_direction(&w.tags);
 
-        self.create_edges(&nodes, penalty, forward, backward);
-        self.update_state_after_adding_way(w.id, nodes);
+        self.create_edges(&w.tags, &nodes, forward, backward);
+        self.try_update_state_after_adding_way(w.id, nodes)
     }
 
     /// Gets the [penalty](crate::osm::profile::Penalty) applicable for the provided
@@ -133,35 +552,44 @@ impl<'a> GraphBuilder<'a> {
         }
     }
 
-    fn get_way_nodes(&self, w: &model::Way) -> Vec<i64> {
+    fn try_get_way_nodes(&self, w: &model::Way) -> Result<Vec<i64>, TryReserveError> {
         // Check if way has enough nodes
         if w.nodes.len() < 2 {
             log::warn!(target: "routex.osm", "way {} has less than 2 nodes - ignoring", w.id);
-            return vec![];
+            return Ok(vec![]);
         }
 
         // Filter out invalid nodes
         // NOTE: We don't warn about invalid references, as they may have been deliberately
         //       filtered out by the bbox. We're not an osm validator.
-        let nodes: Vec<i64> = w
-            .nodes
-            .iter()
-            .cloned()
-            .filter(|&node_id| self.g.get_node(node_id).is_some())
-            .collect();
-
-        if nodes.len() < 2 {
-            vec![]
-        } else {
-            nodes
-        }
+        let mut nodes: Vec<i64> = Vec::new();
+        nodes.try_reserve_exact(w.nodes.len())?;
+        nodes.extend(
+            w.nodes
+                .iter()
+                .cloned()
+                .filter(|&node_id| self.g.get_node(node_id).is_some()),
+        );
+
+        Ok(if nodes.len() < 2 { vec![] } else { nodes })
     }
 
-    fn create_edges(&mut self, nodes: &[i64], penalty: f32, forward: bool, backward: bool) {
+    fn create_edges(
+        &mut self,
+        tags: &HashMap<String, String>,
+        nodes: &[i64],
+        forward: bool,
+        backward: bool,
+    ) {
         debug_assert!(nodes.len() >= 2);
-        debug_assert!(penalty.is_finite() && penalty >= 1.0);
         debug_assert!(forward || backward);
 
+        let node_penalty = |id: i64| -> f32 {
+            self.node_tags
+                .get(&id)
+                .map_or(1.0, |node_tags| self.options.profile.node_penalty(node_tags))
+        };
+
         nodes.windows(2).for_each(|pair| {
             let left = self
                 .g
@@ -173,7 +601,16 @@ impl<'a> GraphBuilder<'a> {
                 .get_node(pair[1])
                 .expect("get_way_nodes should only return nodes which exist");
 
-            let cost = penalty * earth_distance(left.lat, left.lon, right.lat, right.lon);
+            let distance = earth_distance(left.lat, left.lon, right.lat, right.lon);
+            let cost = self.options.profile.edge_cost(tags, distance)
+                * node_penalty(left.id)
+                * node_penalty(right.id);
+
+            // A blocking barrier on either endpoint makes the edge non-routable in that
+            // direction - mirrors get_way_penalty skipping an infinite-penalty way outright.
+            if !cost.is_finite() {
+                return;
+            }
 
             if forward {
                 self.g.set_edge(left.id, Edge { to: right.id, cost });
@@ -184,11 +621,17 @@ impl<'a> GraphBuilder<'a> {
         });
     }
 
-    fn update_state_after_adding_way(&mut self, way_id: i64, nodes: Vec<i64>) {
+    fn try_update_state_after_adding_way(
+        &mut self,
+        way_id: i64,
+        nodes: Vec<i64>,
+    ) -> Result<(), TryReserveError> {
         nodes.iter().for_each(|node_id| {
             self.unused_nodes.remove(node_id);
         });
+        self.way_nodes.try_reserve(1)?;
         self.way_nodes.insert(way_id, nodes);
+        Ok(())
     }
 
     fn add_relation(&mut self, r: model::Relation) {
@@ -199,14 +642,24 @@ impl<'a> GraphBuilder<'a> {
     }
 
     fn add_relation_inner(&mut self, r: &model::Relation) -> Result<(), InvalidRestriction> {
-        let kind = self.options.profile.restriction_kind(&r.tags);
-        if kind == TurnRestriction::Inapplicable {
-            return Ok(());
-        }
+        match self.options.profile.restriction_kind(&r.tags) {
+            TurnRestriction::Inapplicable => Ok(()),
+
+            // no_entry/no_exit expand into one ordinary prohibitory restriction per
+            // from/to combination - a pairing disjoint or referencing unknown
+            // nodes/ways is simply skipped, rather than discarding the whole relation.
+            TurnRestriction::NoEntry | TurnRestriction::NoExit => {
+                for nodes in self.get_restriction_node_sets(r) {
+                    self.store_restriction(r.id, &nodes, TurnRestriction::Prohibitory)?;
+                }
+                Ok(())
+            }
 
-        let nodes = self.get_restriction_nodes(&r)?;
-        self.store_restriction(&nodes, kind)?;
-        Ok(())
+            kind => {
+                let nodes = self.get_restriction_nodes(r)?;
+                self.store_restriction(r.id, &nodes, kind)
+            }
+        }
     }
 
     /// Returns the sequence of nodes representing a turn restriction.
@@ -221,6 +674,73 @@ impl<'a> GraphBuilder<'a> {
         self.flatten_member_nodes(&mut member_nodes)
     }
 
+    /// Returns the node sequence of every from/to combination of a `no_entry`/`no_exit`
+    /// restriction (see [TurnRestriction::NoEntry]/[TurnRestriction::NoExit]), sharing the
+    /// same `via` members. A combination whose members don't resolve to a continuous
+    /// node sequence (e.g. a disjoint or unknown-reference member) is silently dropped,
+    /// rather than discarding every other combination of the same relation.
+    fn get_restriction_node_sets(&self, r: &model::Relation) -> Vec<Vec<i64>> {
+        let orderings = match Self::get_ordered_restriction_members_multi(r) {
+            Ok(orderings) => orderings,
+            Err(e) => {
+                e.log(r.id);
+                return vec![];
+            }
+        };
+
+        orderings
+            .into_iter()
+            .filter_map(|members| {
+                let mut member_nodes = members
+                    .iter()
+                    .map(|&m| self.get_relation_member_nodes(m))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()?;
+                self.flatten_member_nodes(&mut member_nodes).ok()
+            })
+            .collect()
+    }
+
+    /// Returns one from-via-...-via-to member ordering per combination of the relation's
+    /// `from` and `to` members (their Cartesian product), sharing the same `via` members -
+    /// for `no_entry` (single `from`, one or more `to`) and `no_exit` (one or more `from`,
+    /// single `to`) restrictions. Ensures there is at least one `from` and `to` member.
+    fn get_ordered_restriction_members_multi(
+        r: &model::Relation,
+    ) -> Result<Vec<Vec<&model::RelationMember>>, InvalidRestriction> {
+        let mut froms: Vec<&model::RelationMember> = vec![];
+        let mut vias: Vec<&model::RelationMember> = vec![];
+        let mut tos: Vec<&model::RelationMember> = vec![];
+
+        for m in &r.members {
+            match m.role.as_str() {
+                "from" => froms.push(m),
+                "via" => vias.push(m),
+                "to" => tos.push(m),
+                _ => {}
+            }
+        }
+
+        if froms.is_empty() {
+            return Err(InvalidRestriction::MissingFromMember);
+        }
+        if tos.is_empty() {
+            return Err(InvalidRestriction::MissingToMember);
+        }
+
+        let mut orderings = Vec::with_capacity(froms.len() * tos.len());
+        for &from in &froms {
+            for &to in &tos {
+                let mut order = vias.clone();
+                order.insert(0, from);
+                order.push(to);
+                orderings.push(order);
+            }
+        }
+
+        Ok(orderings)
+    }
+
     /// Returns a list of turn restriction members in the order of from-via-...-via-to.
     /// Ensures there is exactly one `from` and `to``, and at least one `via` member.
     fn get_ordered_restriction_members<'r>(
@@ -358,6 +878,7 @@ impl<'a> GraphBuilder<'a> {
 
     fn store_restriction(
         &mut self,
+        relation_id: i64,
         nodes: &[i64],
         kind: TurnRestriction,
     ) -> Result<(), InvalidRestriction> {
@@ -388,10 +909,18 @@ impl<'a> GraphBuilder<'a> {
                 change.remove_edge(a, b);
             },
 
-            TurnRestriction::Inapplicable => assert!(false, "GraphBuilder::store_restriction should not be called with TurnRestriction::Inapplicable")
+            TurnRestriction::Inapplicable | TurnRestriction::NoEntry | TurnRestriction::NoExit => {
+                unreachable!("GraphBuilder::store_restriction is only called with TurnRestriction::Mandatory/Prohibitory")
+            }
         }
 
+        let relation_change = change.as_relation_change(self.g);
         change.apply(self);
+        self.relation_changes
+            .entry(relation_id)
+            .or_default()
+            .merge(relation_change);
+
         return Ok(());
     }
 }
@@ -438,6 +967,33 @@ impl InvalidRestriction {
     }
 }
 
+/// Bookkeeping recorded for a single ingested restriction `relation` so
+/// [GraphBuilder::remove_relation] can later undo it exactly - the inverse of the
+/// [GraphChange] that [GraphBuilder::store_restriction] applied.
+#[derive(Debug, Default)]
+struct RelationChange {
+    /// Phantom nodes created for this relation. Deleting one also deletes its edges.
+    phantom_nodes: Vec<i64>,
+
+    /// Edges added while storing this relation - deleted on undo.
+    added_edges: Vec<(i64, i64)>,
+
+    /// Edges that existed before this relation was stored and were removed while storing it -
+    /// restored, with their original cost, on undo.
+    removed_edges: Vec<(i64, i64, f32)>,
+}
+
+impl RelationChange {
+    /// Folds in the bookkeeping of another restriction expanded from the same relation (e.g.
+    /// one of several `no_entry`/`no_exit` pairings), so [GraphBuilder::remove_relation] undoes
+    /// every one of them together.
+    fn merge(&mut self, other: RelationChange) {
+        self.phantom_nodes.extend(other.phantom_nodes);
+        self.added_edges.extend(other.added_edges);
+        self.removed_edges.extend(other.removed_edges);
+    }
+}
+
 struct GraphChange {
     /// Map of nodes to clone (including their outgoing edges),
     /// mapping new node ids to old node ids.
@@ -453,6 +1009,10 @@ struct GraphChange {
 
     /// New value for [GraphBuilder::phantom_node_id_counter].
     phantom_node_id_counter: i64,
+
+    /// Copy of [GraphBuilder::phantom_free_list] - [GraphChange::clone_node] prefers reusing
+    /// an id from here over growing [phantom_node_id_counter](Self::phantom_node_id_counter).
+    free_list: Vec<i64>,
 }
 
 impl GraphChange {
@@ -462,6 +1022,7 @@ impl GraphChange {
             edges_to_remove: HashSet::default(),
             edges_to_add: HashMap::default(),
             phantom_node_id_counter: b.phantom_node_id_counter,
+            free_list: b.phantom_free_list.clone(),
         }
     }
 
@@ -506,8 +1067,10 @@ impl GraphChange {
     }
 
     fn clone_node(&mut self, src: i64) -> i64 {
-        self.phantom_node_id_counter += 1;
-        let dst = self.phantom_node_id_counter;
+        let dst = self.free_list.pop().unwrap_or_else(|| {
+            self.phantom_node_id_counter += 1;
+            self.phantom_node_id_counter
+        });
         self.new_nodes.insert(dst, src);
         dst
     }
@@ -563,13 +1126,51 @@ impl GraphChange {
         self.edges_to_remove.insert((from, to));
     }
 
+    /// Soft alternative to [GraphChange::remove_edge]: instead of banning the `from -> to`
+    /// movement outright, re-adds it with `penalty` added on top of its original cost and
+    /// does *not* mark it for removal. Both the unpenalized and the penalized movement stay
+    /// routable afterwards, so the router only takes the penalized one when it's globally
+    /// optimal - useful for turn restrictions that should discourage rather than prohibit
+    /// (e.g. an awkward crossing of oncoming traffic).
+    fn penalize_edge(&mut self, g: &Graph, from: i64, to: i64, penalty: f32) {
+        let cost = self.get_edge_cost(g, from, to) + penalty;
+        self.edges_to_add
+            .entry(from)
+            .or_insert_with(HashMap::new)
+            .insert(to, cost);
+    }
+
     fn apply(&self, b: &mut GraphBuilder<'_>) {
         b.phantom_node_id_counter = self.phantom_node_id_counter;
+        b.phantom_free_list = self.free_list.clone();
         self.apply_clone_nodes(b.g);
         self.apply_remove_edges(b.g);
         self.apply_add_edges(b.g);
     }
 
+    /// Captures the bookkeeping [GraphBuilder::remove_relation] needs to undo this change,
+    /// before it's [applied](Self::apply) - in particular, the original cost of each removed
+    /// edge must be read from `g` while the edge is still there.
+    fn as_relation_change(&self, g: &Graph) -> RelationChange {
+        RelationChange {
+            phantom_nodes: self.new_nodes.keys().copied().collect(),
+            added_edges: self
+                .edges_to_add
+                .iter()
+                .flat_map(|(&from, edges)| edges.keys().map(move |&to| (from, to)))
+                .collect(),
+            // Edges whose `from` is itself a phantom node created by this same change are
+            // excluded: that node is deleted wholesale on undo, taking the edge with it, and
+            // it never existed before this change, so there's nothing to restore.
+            removed_edges: self
+                .edges_to_remove
+                .iter()
+                .filter(|(from, _)| !self.new_nodes.contains_key(from))
+                .map(|&(from, to)| (from, to, g.get_edge(from, to)))
+                .collect(),
+        }
+    }
+
     fn apply_clone_nodes(&self, g: &mut Graph) {
         for (&new_id, &old_id) in &self.new_nodes {
             let old_node = g
@@ -602,7 +1203,7 @@ impl GraphChange {
     }
 }
 
-fn is_bbox_applicable(bbox: [f32; 4]) -> bool {
+pub(super) fn is_bbox_applicable(bbox: [f32; 4]) -> bool {
     // All elements 0 - no bbox
     if bbox.iter().all(|&x| x == 0.0) {
         return false;
@@ -666,6 +1267,7 @@ mod tests {
                 id: $id,
                 nodes: $nodes,
                 tags: HashMap::default(),
+                meta: None,
             }
         };
 
@@ -674,6 +1276,7 @@ mod tests {
                 id: $id,
                 nodes: $nodes,
                 tags: $tags,
+                meta: None,
             }
         };
     }
@@ -694,6 +1297,7 @@ mod tests {
                 id: $id,
                 members: $members,
                 tags: HashMap::default(),
+                meta: None,
             }
         };
 
@@ -702,6 +1306,7 @@ mod tests {
                 id: $id,
                 members: $members,
                 tags: $tags,
+                meta: None,
             }
         };
     }
@@ -731,6 +1336,7 @@ mod tests {
         profile: &CAR_PROFILE,
         file_format: FileFormat::Xml,
         bbox: [0.0; 4],
+        include_metadata: false,
     };
 
     mod graph_builder {
@@ -862,6 +1468,32 @@ mod tests {
             assert_no_edge!(g, 3, 1);
         }
 
+        #[test]
+        fn test_add_way_with_barrier_node() {
+            let mut g = Graph::default();
+
+            {
+                let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+                b.add_node(n!(1, 0.0, 0.0));
+                b.node_tags.insert(2, tags!("barrier": "gate"));
+                b.add_node(n!(2, 0.1, 0.0));
+                b.add_way(w!(1, vec![1, 2], tags!("highway": "primary")));
+
+                b.add_node(n!(3, 0.0, 1.0));
+                b.node_tags
+                    .insert(4, tags!("barrier": "gate", "motor_vehicle": "yes"));
+                b.add_node(n!(4, 0.1, 1.0));
+                b.add_way(w!(2, vec![3, 4], tags!("highway": "primary")));
+            }
+
+            // Node 2 is a plain CAR_PROFILE-blocking gate - no edge reaches or leaves it.
+            assert_no_edge!(g, 1, 2);
+            assert_no_edge!(g, 2, 1);
+            // Node 4's motor_vehicle=yes override whitelists the gate for CAR_PROFILE.
+            assert_edge!(g, 3, 4);
+            assert_edge!(g, 4, 3);
+        }
+
         #[test]
         fn test_add_relation_prohibitory() {
             //     4
@@ -1095,6 +1727,114 @@ mod tests {
             assert_no_edge!(g, 101, 5);
         }
 
+        #[test]
+        fn test_add_relation_no_entry() {
+            //     4
+            //     │
+            // 1───2───3
+            //     │
+            //     5
+            // no_entry: 1->2->{4,5}
+
+            let mut g = Graph::default();
+
+            {
+                let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+                b.phantom_node_id_counter = 100;
+
+                b.add_node(n!(1, 0.0, 0.0));
+                b.add_node(n!(2, 0.1, 0.0));
+                b.add_node(n!(3, 0.2, 0.0));
+                b.add_node(n!(4, 0.1, 0.1));
+                b.add_node(n!(5, 0.1, -0.1));
+                b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+                b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+                b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+                b.add_way(w!(13, vec![2, 5], tags!("highway": "primary")));
+                b.add_relation(r!(
+                    20,
+                    vec![
+                        m!(FeatureType::Way, 10, "from"),
+                        m!(FeatureType::Node, 2, "via"),
+                        m!(FeatureType::Way, 12, "to"),
+                        m!(FeatureType::Way, 13, "to"),
+                    ],
+                    tags!("type": "restriction", "restriction": "no_entry")
+                ));
+            }
+
+            assert_no_edge!(g, 1, 2);
+            assert_edge!(g, 1, 101);
+
+            assert_edge!(g, 2, 1);
+            assert_edge!(g, 2, 3);
+            assert_edge!(g, 2, 4);
+            assert_edge!(g, 2, 5);
+
+            assert_edge!(g, 101, 1);
+            assert_no_edge!(g, 101, 2);
+            assert_edge!(g, 101, 3);
+            assert_no_edge!(g, 101, 4);
+            assert_no_edge!(g, 101, 5);
+        }
+
+        #[test]
+        fn test_add_relation_no_exit() {
+            //     4
+            //     │
+            // 1───2───3
+            //     │
+            //     5
+            // no_exit: {1,3}->2->4
+
+            let mut g = Graph::default();
+
+            {
+                let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+                b.phantom_node_id_counter = 100;
+
+                b.add_node(n!(1, 0.0, 0.0));
+                b.add_node(n!(2, 0.1, 0.0));
+                b.add_node(n!(3, 0.2, 0.0));
+                b.add_node(n!(4, 0.1, 0.1));
+                b.add_node(n!(5, 0.1, -0.1));
+                b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+                b.add_way(w!(11, vec![3, 2], tags!("highway": "primary")));
+                b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+                b.add_way(w!(13, vec![2, 5], tags!("highway": "primary")));
+                b.add_relation(r!(
+                    20,
+                    vec![
+                        m!(FeatureType::Way, 10, "from"),
+                        m!(FeatureType::Way, 11, "from"),
+                        m!(FeatureType::Node, 2, "via"),
+                        m!(FeatureType::Way, 12, "to"),
+                    ],
+                    tags!("type": "restriction", "restriction": "no_exit")
+                ));
+            }
+
+            // from=10 (1->2)
+            assert_no_edge!(g, 1, 2);
+            assert_edge!(g, 1, 101);
+            assert_edge!(g, 101, 1);
+            assert_no_edge!(g, 101, 4);
+            assert_edge!(g, 101, 5);
+
+            // from=11 (3->2)
+            assert_no_edge!(g, 3, 2);
+            assert_edge!(g, 3, 102);
+            assert_edge!(g, 102, 3);
+            assert_no_edge!(g, 102, 4);
+            assert_edge!(g, 102, 5);
+
+            // unrestricted movements through 2 stay intact
+            assert_edge!(g, 2, 1);
+            assert_edge!(g, 2, 3);
+            assert_edge!(g, 2, 4);
+            assert_edge!(g, 2, 5);
+        }
+
         #[test]
         fn test_add_relation_mandatory() {
             //     4
@@ -1471,7 +2211,7 @@ mod tests {
                 assert!(b.unused_nodes.contains(&4));
                 assert!(b.unused_nodes.contains(&5));
 
-                b.cleanup();
+                b.finish();
             }
 
             assert_eq!(g.len(), 3);
@@ -1479,6 +2219,425 @@ mod tests {
             assert!(g.get_node(2).is_some());
             assert!(g.get_node(3).is_some());
         }
+
+        #[test]
+        fn test_apply_turn_costs_straight_through_no_clone() {
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 0.2, 0.0));
+            b.add_way(w!(10, vec![1, 2, 3], tags!("highway": "primary")));
+
+            let cost_2_3 = b.g.get_edge(2, 3);
+            b.finish();
+
+            // Node 2 touches only 2 distinct neighbors - no ambiguity to disambiguate, so no
+            // cloning, and the (collinear, so zero-angle) turn adds no cost.
+            assert_eq!(g.len(), 3);
+            assert_eq!(g.get_edge(2, 3), cost_2_3);
+        }
+
+        #[test]
+        fn test_apply_turn_costs_dead_end_u_turn() {
+            // 1 ──────── 2
+            // Node 2 is a true dead end - its only neighbor is 1, used both ways - so
+            // leaving it can only mean turning back the way you came.
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+
+            let cost_2_1 = b.g.get_edge(2, 1);
+            b.finish();
+
+            let from_heading = earth_bearing(0.0, 0.0, 0.1, 0.0);
+            let to_heading = earth_bearing(0.1, 0.0, 0.0, 0.0);
+            let no_tags = HashMap::<String, String>::new();
+            assert_eq!(
+                g.get_edge(2, 1),
+                cost_2_1 + CAR_PROFILE.turn_cost(from_heading, to_heading, &no_tags)
+            );
+        }
+
+        #[test]
+        fn test_apply_turn_costs_clones_real_junction() {
+            //            3
+            //            │
+            // 1 ──────── 2 ──────── 4
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.phantom_node_id_counter = 100;
+
+            b.add_node(n!(1, -0.1, 0.0));
+            b.add_node(n!(2, 0.0, 0.0));
+            b.add_node(n!(3, 0.1, 0.0));
+            b.add_node(n!(4, 0.0, 0.1));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+            b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+
+            let cost_1_2 = b.g.get_edge(1, 2);
+            let cost_2_1 = b.g.get_edge(2, 1);
+            let cost_2_3 = b.g.get_edge(2, 3);
+            let cost_2_4 = b.g.get_edge(2, 4);
+            let cost_4_2 = b.g.get_edge(4, 2);
+
+            b.finish();
+
+            // Node 2 touches 3 distinct neighbors - a real junction. Predecessor 1, the
+            // lowest-numbered, is kept un-redirected - so 2 stays reachable under its
+            // canonical id, e.g. for SpatialIndex-resolved queries - with its own edges
+            // adjusted in place for that one arrival direction; predecessors 3 and 4 are
+            // redirected into their own clones (101 and 102, given the counter reset above).
+            assert_eq!(g.len(), 6);
+
+            let from_1_heading = earth_bearing(-0.1, 0.0, 0.0, 0.0);
+            let to_1_heading = earth_bearing(0.0, 0.0, -0.1, 0.0);
+            let to_3_heading = earth_bearing(0.0, 0.0, 0.1, 0.0);
+            let to_4_heading = earth_bearing(0.0, 0.0, 0.0, 0.1);
+            let no_tags = HashMap::<String, String>::new();
+
+            // Node 1 is itself a dead end (its only neighbor is 2), so its only edge gets a
+            // U-turn cost too - see
+            // test_apply_turn_costs_dead_end_u_turn for that in isolation.
+            assert_eq!(
+                g.get_edge(1, 2),
+                cost_1_2 + CAR_PROFILE.turn_cost(to_1_heading, from_1_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(2, 1),
+                cost_2_1 + CAR_PROFILE.turn_cost(from_1_heading, to_1_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(2, 3),
+                cost_2_3 + CAR_PROFILE.turn_cost(from_1_heading, to_3_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(2, 4),
+                cost_2_4 + CAR_PROFILE.turn_cost(from_1_heading, to_4_heading, &no_tags)
+            );
+
+            // Predecessor 4 is redirected into its own clone (102). 4 is itself a dead end
+            // (its only neighbor is 2), so the redirecting edge also carries 4's own U-turn
+            // cost - same as any other dead end, see test_apply_turn_costs_dead_end_u_turn.
+            let from_4_heading = earth_bearing(0.0, 0.1, 0.0, 0.0);
+            assert_no_edge!(g, 4, 2);
+            assert_eq!(
+                g.get_edge(4, 102),
+                cost_4_2 + CAR_PROFILE.turn_cost(to_4_heading, from_4_heading, &no_tags)
+            );
+
+            // ...with turn_cost folded into each of the clone's outgoing edges, based on the
+            // heading actually turned through coming from node 4.
+            assert_eq!(
+                g.get_edge(102, 1),
+                cost_2_1 + CAR_PROFILE.turn_cost(from_4_heading, to_1_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(102, 3),
+                cost_2_3 + CAR_PROFILE.turn_cost(from_4_heading, to_3_heading, &no_tags)
+            );
+        }
+
+        #[test]
+        fn test_apply_turn_costs_adjacent_real_junctions() {
+            //       3           5
+            //       │           │
+            // 1 ─── 2 ───────── 4 ─── 6
+            //
+            // Two real junctions (2 and 4) directly connected to each other. 4 is a non-kept
+            // predecessor of 2, so the 4->2 edge must be redirected into 2's clone for
+            // arrivals from 4 - even though 2 is in turn 4's own kept predecessor, whose
+            // in-place edges start at 4 itself.
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.phantom_node_id_counter = 100;
+
+            b.add_node(n!(1, 0.0, -0.1));
+            b.add_node(n!(2, 0.0, 0.0));
+            b.add_node(n!(3, 0.1, 0.0));
+            b.add_node(n!(4, 0.0, 0.1));
+            b.add_node(n!(5, 0.1, 0.1));
+            b.add_node(n!(6, 0.0, 0.2));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+            b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+            b.add_way(w!(13, vec![4, 5], tags!("highway": "primary")));
+            b.add_way(w!(14, vec![4, 6], tags!("highway": "primary")));
+
+            let cost_4_2 = b.g.get_edge(4, 2);
+            let cost_4_5 = b.g.get_edge(4, 5);
+            let cost_4_6 = b.g.get_edge(4, 6);
+            let cost_5_4 = b.g.get_edge(5, 4);
+            let cost_6_4 = b.g.get_edge(6, 4);
+
+            b.finish();
+
+            // 2's predecessors are {1, 3, 4}; 1 is kept, so 3 and 4 are redirected into
+            // their own clones (101 and 102). 4's predecessors are {2, 5, 6}; 2 is kept
+            // (it's the lowest-numbered), so 5 and 6 are redirected into their own clones
+            // (103 and 104).
+            assert_eq!(g.len(), 10);
+
+            // 4's kept predecessor is 2, so the edge back to 2 (a U-turn at the junction) is
+            // adjusted in place before being redirected to 2's clone - it's not a bare copy
+            // of the original cost.
+            let from_2_heading = earth_bearing(0.0, 0.0, 0.0, 0.1);
+            let to_2_heading = earth_bearing(0.0, 0.1, 0.0, 0.0);
+            let no_tags = HashMap::<String, String>::new();
+            assert_no_edge!(g, 4, 2);
+            assert_eq!(
+                g.get_edge(4, 102),
+                cost_4_2 + CAR_PROFILE.turn_cost(from_2_heading, to_2_heading, &no_tags)
+            );
+
+            // 5 and 6 are non-kept predecessors of 4, and are themselves dead ends (their
+            // only neighbor is 4), so their redirecting edges also carry their own U-turn
+            // cost - same as any other dead end.
+            let to_5_heading = earth_bearing(0.0, 0.1, 0.1, 0.1);
+            let to_6_heading = earth_bearing(0.0, 0.1, 0.0, 0.2);
+            let back_from_5_heading = earth_bearing(0.1, 0.1, 0.0, 0.1);
+            let back_from_6_heading = earth_bearing(0.0, 0.2, 0.0, 0.1);
+            assert_no_edge!(g, 5, 4);
+            assert_no_edge!(g, 6, 4);
+            assert_eq!(
+                g.get_edge(5, 103),
+                cost_5_4 + CAR_PROFILE.turn_cost(to_5_heading, back_from_5_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(6, 104),
+                cost_6_4 + CAR_PROFILE.turn_cost(to_6_heading, back_from_6_heading, &no_tags)
+            );
+
+            // 4's own outgoing edges to 5 and 6 - its kept predecessor is 2, so these are
+            // adjusted in place rather than cloned, same as any other junction's kept
+            // direction.
+            assert_eq!(
+                g.get_edge(4, 5),
+                cost_4_5 + CAR_PROFILE.turn_cost(from_2_heading, to_5_heading, &no_tags)
+            );
+            assert_eq!(
+                g.get_edge(4, 6),
+                cost_4_6 + CAR_PROFILE.turn_cost(from_2_heading, to_6_heading, &no_tags)
+            );
+        }
+
+        #[test]
+        fn test_apply_turn_costs_chained_junctions_use_adjusted_cost() {
+            //           8
+            //           │
+            //     3 ─── 2 ─── 4 ─── 6
+            //                 │
+            //                 1
+            //
+            // 2 and 4 are both real junctions, directly connected. 2's kept predecessor is
+            // 3, so the 2->4 edge carries the 3->2->4 turn cost. 2 isn't 4's kept
+            // predecessor (1 is, being the lowest id), so that edge points at the clone of 4
+            // for arrivals from 2 instead - and it must carry the turn-adjusted 2->4 cost,
+            // not the raw one.
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.phantom_node_id_counter = 100;
+
+            b.add_node(n!(3, 0.0, -0.1));
+            b.add_node(n!(2, 0.0, 0.0));
+            b.add_node(n!(8, -0.1, 0.0));
+            b.add_node(n!(4, 0.1, 0.0));
+            b.add_node(n!(1, 0.2, 0.0));
+            b.add_node(n!(6, 0.1, 0.1));
+            b.add_way(w!(10, vec![2, 3], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 8], tags!("highway": "primary")));
+            b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+            b.add_way(w!(13, vec![4, 1], tags!("highway": "primary")));
+            b.add_way(w!(14, vec![4, 6], tags!("highway": "primary")));
+
+            let cost_2_4 = b.g.get_edge(2, 4);
+
+            b.finish();
+
+            // 4 clones: 101/102 for 2's non-kept predecessors (4 and 8), 103/104 for 4's
+            // non-kept predecessors (2 and 6).
+            assert_eq!(g.len(), 10);
+
+            let from_3_heading = earth_bearing(0.0, -0.1, 0.0, 0.0);
+            let to_4_heading = earth_bearing(0.0, 0.0, 0.1, 0.0);
+            let no_tags = HashMap::<String, String>::new();
+            let baked_2_4 = cost_2_4 + CAR_PROFILE.turn_cost(from_3_heading, to_4_heading, &no_tags);
+
+            // The 2->4 edge is redirected to 4's clone for arrivals from 2 (103); the
+            // redirect must carry `baked_2_4`, not `cost_2_4`.
+            assert_no_edge!(g, 2, 4);
+            assert_eq!(g.get_edge(2, 103), baked_2_4);
+        }
+
+        #[test]
+        fn test_prune_disconnected_min_size() {
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+
+            // Main component: 1-2-3; tiny island: 4-5
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 0.2, 0.0));
+            b.add_node(n!(4, 1.0, 1.0));
+            b.add_node(n!(5, 1.1, 1.0));
+            b.add_way(w!(10, vec![1, 2, 3], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![4, 5], tags!("highway": "primary")));
+
+            let mut removed = b.prune_disconnected(3, None);
+            removed.sort();
+            assert_eq!(removed, vec![4, 5]);
+
+            assert!(b.g.get_node(1).is_some());
+            assert!(b.g.get_node(2).is_some());
+            assert!(b.g.get_node(3).is_some());
+            assert!(b.g.get_node(4).is_none());
+            assert!(b.g.get_node(5).is_none());
+        }
+
+        #[test]
+        fn test_prune_disconnected_seed() {
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+
+            // Two equally-sized, disconnected components.
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 1.0, 1.0));
+            b.add_node(n!(4, 1.1, 1.0));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![3, 4], tags!("highway": "primary")));
+
+            let mut removed = b.prune_disconnected(1, Some(1));
+            removed.sort();
+            assert_eq!(removed, vec![3, 4]);
+
+            assert!(b.g.get_node(1).is_some());
+            assert!(b.g.get_node(2).is_some());
+        }
+
+        #[test]
+        fn test_remove_way() {
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 0.2, 0.0));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+
+            assert_edge!(b.g, 1, 2);
+            assert_edge!(b.g, 2, 1);
+            assert!(!b.unused_nodes.contains(&2));
+
+            b.remove_way(10);
+
+            assert_no_edge!(b.g, 1, 2);
+            assert_no_edge!(b.g, 2, 1);
+            assert_edge!(b.g, 2, 3);
+            assert!(b.unused_nodes.contains(&1));
+            assert!(!b.unused_nodes.contains(&2)); // still used by way 11
+
+            // Removing an unknown way is a no-op.
+            b.remove_way(10);
+            b.remove_way(404);
+        }
+
+        #[test]
+        fn test_remove_relation() {
+            //     4
+            //     │
+            // 1───2───3
+            // no_left_turn: 1->2->4
+
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.phantom_node_id_counter = 100;
+
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 0.2, 0.0));
+            b.add_node(n!(4, 0.1, 0.1));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+            b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+            b.add_relation(r!(
+                20,
+                vec![
+                    m!(FeatureType::Way, 10, "from"),
+                    m!(FeatureType::Node, 2, "via"),
+                    m!(FeatureType::Way, 12, "to"),
+                ],
+                tags!("type": "restriction", "restriction": "no_left_turn")
+            ));
+
+            assert_no_edge!(b.g, 1, 2);
+            assert_edge!(b.g, 1, 101);
+
+            let original_cost = b.g.get_edge(1, 101);
+            b.remove_relation(20);
+
+            assert!(b.g.get_node(101).is_none());
+            assert_edge!(b.g, 1, 2);
+            assert_eq!(b.g.get_edge(1, 2), original_cost);
+            assert_edge!(b.g, 2, 3);
+            assert_edge!(b.g, 2, 4);
+
+            // The freed phantom id is reused by the next restriction.
+            b.add_relation(r!(
+                21,
+                vec![
+                    m!(FeatureType::Way, 10, "from"),
+                    m!(FeatureType::Node, 2, "via"),
+                    m!(FeatureType::Way, 12, "to"),
+                ],
+                tags!("type": "restriction", "restriction": "no_left_turn")
+            ));
+            assert!(b.g.get_node(101).is_some());
+            assert_eq!(b.phantom_node_id_counter, 101);
+
+            // Removing an unknown relation is a no-op.
+            b.remove_relation(404);
+        }
+
+        #[test]
+        fn test_store_turn_penalty() {
+            //     4
+            //     │
+            // 1───2───3
+
+            let mut g = Graph::default();
+            let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+            b.phantom_node_id_counter = 100;
+
+            b.add_node(n!(1, 0.0, 0.0));
+            b.add_node(n!(2, 0.1, 0.0));
+            b.add_node(n!(3, 0.2, 0.0));
+            b.add_node(n!(4, 0.1, 0.1));
+            b.add_way(w!(10, vec![1, 2], tags!("highway": "primary")));
+            b.add_way(w!(11, vec![2, 3], tags!("highway": "primary")));
+            b.add_way(w!(12, vec![2, 4], tags!("highway": "primary")));
+
+            let original_cost_1_2 = b.g.get_edge(1, 2);
+            let original_cost_2_4 = b.g.get_edge(2, 4);
+            b.store_turn_penalty(20, &[1, 2, 4], 50.0);
+
+            // Unlike store_restriction, the 1-2-4 movement is still routable, just pricier.
+            assert_no_edge!(b.g, 1, 2);
+            assert_edge!(b.g, 1, 101);
+            assert_edge!(b.g, 101, 4);
+            assert_eq!(b.g.get_edge(101, 4), original_cost_2_4 + 50.0);
+            // Other turns through the via node are untouched.
+            assert_edge!(b.g, 2, 3);
+
+            b.remove_relation(20);
+            assert!(b.g.get_node(101).is_none());
+            assert_edge!(b.g, 1, 2);
+            assert_eq!(b.g.get_edge(1, 2), original_cost_1_2);
+        }
     }
 
     mod graph_change {
@@ -1663,6 +2822,28 @@ mod tests {
             assert_eq!(g.get_edges(11), &[e!(12, 200.0)]);
             assert_eq!(g.get_edges(12), &[e!(4, 200.0)]);
         }
+
+        #[test]
+        fn test_penalize_edge() {
+            let mut g = fixture_graph();
+
+            {
+                let mut b = GraphBuilder::new(&mut g, &DEFAULT_OPTIONS);
+                b.phantom_node_id_counter = 10;
+
+                let mut c = GraphChange::new(&b);
+                let cloned = c.restriction_as_cloned_nodes(&b.g, &[1, 2, 3]).unwrap();
+                assert_eq!(cloned, &[1, 11, 3]);
+                c.penalize_edge(&b.g, 11, 3, 50.0);
+                c.apply(&mut b);
+            }
+
+            // The cloned node keeps every edge it started with (unlike ensure_only_edge) -
+            // only the penalized 11-3 movement's cost changed, so other turns remain available.
+            assert_eq!(g.get_edges(1), &[e!(11, 200.0)]);
+            assert_eq!(g.get_edges(2), &[e!(1, 200.0), e!(3, 200.0), e!(5, 100.0)]);
+            assert_eq!(g.get_edges(11), &[e!(1, 200.0), e!(3, 250.0), e!(5, 100.0)]);
+        }
     }
 
     #[test]
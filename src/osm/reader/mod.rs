@@ -6,7 +6,7 @@ use std::io;
 use std::path::Path;
 use std::sync::Arc;
 
-use graph_builder::GraphBuilder;
+pub use graph_builder::{GraphBuilder, TryAddFeaturesError};
 
 use crate::osm::Profile;
 use crate::Graph;
@@ -104,12 +104,19 @@ pub struct Options<'a> {
     /// How OSM features should be interpreted and converted into a [Graph].
     pub profile: &'a Profile<'a>,
 
-    /// Format of the input data. Currently, only [FileFormat::Xml] is supported.
+    /// Format of the input data. [FileFormat::Xml] (optionally gzip/bzip2-compressed)
+    /// and [FileFormat::Pbf] are both supported.
     pub file_format: FileFormat,
 
     /// Filter features by a specific bounding box. In order: left (min lon), bottom (min lat),
     /// right (max lon), top (max lat). Ignored if all values are set to zero.
     pub bbox: [f32; 4],
+
+    /// Whether to extract each object's edit-history [model::Metadata] (version, timestamp,
+    /// changeset, uid, user). Disabled by default since most callers only care about
+    /// geometry/tags, and extracting it costs an extra string-table lookup and allocation
+    /// per object. Only honored by [FileFormat::Pbf] - the XML reader never populates it.
+    pub include_metadata: bool,
 }
 
 /// Trait alias for objects which can stream [osm features](model::Feature)
@@ -145,7 +152,9 @@ pub fn add_features_from_io<'a, R: io::BufRead>(
 
         FileFormat::Xml => {
             let features = xml::features_from_file(reader);
-            GraphBuilder::new(g, options).add_features(features)?;
+            let mut builder = GraphBuilder::new(g, options);
+            builder.add_features(features)?;
+            builder.finish();
             Ok(())
         }
 
@@ -153,7 +162,9 @@ pub fn add_features_from_io<'a, R: io::BufRead>(
             let d = flate2::bufread::MultiGzDecoder::new(reader);
             let b = io::BufReader::new(d);
             let features = xml::features_from_file(b);
-            GraphBuilder::new(g, options).add_features(features)?;
+            let mut builder = GraphBuilder::new(g, options);
+            builder.add_features(features)?;
+            builder.finish();
             Ok(())
         }
 
@@ -161,13 +172,18 @@ pub fn add_features_from_io<'a, R: io::BufRead>(
             let d = bzip2::bufread::MultiBzDecoder::new(reader);
             let b = io::BufReader::new(d);
             let features = xml::features_from_file(b);
-            GraphBuilder::new(g, options).add_features(features)?;
+            let mut builder = GraphBuilder::new(g, options);
+            builder.add_features(features)?;
+            builder.finish();
             Ok(())
         }
 
         FileFormat::Pbf => {
-            let features = pbf::features_from_file(reader);
-            GraphBuilder::new(g, options).add_features(features)?;
+            let query_bbox = graph_builder::is_bbox_applicable(options.bbox).then_some(options.bbox);
+            let features = pbf::features_from_file(reader, options.include_metadata, query_bbox);
+            let mut builder = GraphBuilder::new(g, options);
+            builder.add_features(features)?;
+            builder.finish();
             Ok(())
         }
     }
@@ -193,7 +209,9 @@ pub fn add_features_from_buffer<'a>(
     if options.file_format == FileFormat::Xml {
         // Fast path is available for in-memory XML data
         let features = xml::features_from_buffer(data);
-        GraphBuilder::new(g, options).add_features(features)?;
+        let mut builder = GraphBuilder::new(g, options);
+        builder.add_features(features)?;
+        builder.finish();
         Ok(())
     } else {
         // Wrap the buffer in a cursor and use the IO path
@@ -4,12 +4,30 @@
 use crate::Node;
 use std::collections::HashMap;
 
+/// Edit-history metadata of an OSM object, extracted only when the reader is asked to -
+/// see [Options::include_metadata](super::Options::include_metadata).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// Edit version, starting at 1. `-1` if not present in the source data.
+    pub version: i32,
+
+    /// Milliseconds since the Unix epoch. `0` if not present in the source data.
+    pub timestamp: i64,
+
+    pub changeset: i64,
+    pub uid: i32,
+
+    /// Empty if not present in the source data.
+    pub user: String,
+}
+
 /// Represents an [OSM way](https://wiki.openstreetmap.org/wiki/Way).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Way {
     pub id: i64,
     pub nodes: Vec<i64>,
     pub tags: HashMap<String, String>,
+    pub meta: Option<Metadata>,
 }
 
 /// Type of an [OSM feature/element](https://wiki.openstreetmap.org/wiki/Elements).
@@ -44,12 +62,20 @@ pub struct Relation {
     pub id: i64,
     pub members: Vec<RelationMember>,
     pub tags: HashMap<String, String>,
+    pub meta: Option<Metadata>,
 }
 
 /// Union over all possible [OSM features/elements](https://wiki.openstreetmap.org/wiki/Elements).
+///
+/// Unlike [Way]/[Relation], neither a node's tags nor its [Metadata] are part of [Node]
+/// itself - [Node] is the `#[repr(C)]` vertex shared with the [Graph](crate::Graph) and the
+/// C bindings, so it can't carry owned, variable-sized data. [Feature::Node] pairs it with
+/// its tags and metadata instead, consumed only by [GraphBuilder](super::GraphBuilder) to
+/// evaluate [Profile::node_penalty](crate::osm::Profile::node_penalty) and
+/// [Profile::turn_cost](crate::osm::Profile::turn_cost).
 #[derive(Debug, Clone)]
 pub enum Feature {
-    Node(Node),
+    Node(Node, HashMap<String, String>, Option<Metadata>),
     Way(Way),
     Relation(Relation),
 }
@@ -4,15 +4,17 @@
 mod fileformat;
 mod osmformat;
 
-use super::model::{Feature, FeatureType, Relation, RelationMember, Way};
+use super::model::{Feature, FeatureType, Metadata, Relation, RelationMember, Way};
 use crate::Node;
 
 use protobuf::Message;
 use std::collections::HashMap;
 use std::io;
-use std::io::Read;
+use std::io::{Read, Seek};
 use std::rc::Rc;
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::thread;
 
 /// Max permitted size for a serialized [blob header](https://wiki.openstreetmap.org/wiki/PBF_Format#File_format) -
 /// 64 KiB.
@@ -48,7 +50,7 @@ pub enum Error {
     #[error("BlobHeader.datasize is negative")]
     NegativeBlobHeaderSize,
 
-    #[error("unsupported compression: {0} (supported: raw, zlib and bzip2)")]
+    #[error("unsupported compression: {0} (supported: raw, zlib, bzip2, lz4 and zstd)")]
     UnsupportedCompression(&'static str),
 
     #[error("file requires unsupported features: {0:?}")]
@@ -67,9 +69,195 @@ impl From<protobuf::Error> for Error {
     }
 }
 
-/// Returns an iterator over all features from an OSM PBF file.
-pub fn features_from_file<R: io::Read>(reader: R) -> impl Iterator<Item = Result<Feature, Error>> {
-    File(reader).features()
+/// Returns an iterator over all features from an OSM PBF file. `include_metadata` controls
+/// whether each object's [Metadata] is also extracted from its `Info`/`DenseInfo` block -
+/// disabled, this costs nothing beyond what geometry/tag parsing already pays. `query_bbox`,
+/// if given, uses the same `[left, bottom, right, top]` layout as [Options::bbox](super::Options::bbox)
+/// and lets whole blocks be skipped - see [block_bbox] - at the cost of also dropping any
+/// way/relation that happens to live in a block whose nodes all lie outside `query_bbox`.
+/// A block with no nodes at all is never skipped.
+pub fn features_from_file<R: io::Read>(
+    reader: R,
+    include_metadata: bool,
+    query_bbox: Option<[f32; 4]>,
+) -> impl Iterator<Item = Result<Feature, Error>> {
+    File(reader).features(include_metadata, query_bbox)
+}
+
+/// Returns an iterator over all features from an OSM PBF file, like [features_from_file],
+/// but decompressing and parsing each block across a pool of `num_threads` worker threads
+/// instead of one block at a time on the calling thread.
+///
+/// Blocks are fully self-contained (each carries its own string table and coordinate
+/// offsets), so once raw `(BlobHeader, Blob)` bytes have been read off `reader` - which
+/// stays single-threaded, since it's I/O bound and must happen in file order anyway - their
+/// decompression and protobuf parsing can run concurrently. Results are re-emitted through
+/// a reorder buffer keyed by blob index, so the returned iterator yields features in
+/// the same order [features_from_file] would, just faster on multi-core machines. The
+/// reorder buffer has no size cap: blobs that finish ahead of a slower earlier one are all
+/// held until that one arrives.
+pub fn features_from_file_parallel<R: io::Read + Send + 'static>(
+    reader: R,
+    num_threads: usize,
+    include_metadata: bool,
+    query_bbox: Option<[f32; 4]>,
+) -> impl Iterator<Item = Result<Feature, Error>> {
+    ParallelFeatures::new(reader, num_threads, include_metadata, query_bbox)
+}
+
+/// Returns the dataset-wide bounding box declared in the file's `OSMHeader` block, if any -
+/// `None` if the header carries no `HeaderBBox` at all. Unlike [features_from_file], this
+/// only reads the leading header blob, not any `OSMData` block.
+pub fn header_bbox_from_file<R: io::Read>(reader: R) -> Result<Option<[f32; 4]>, Error> {
+    let mut blocks = FileBlocks::new(reader);
+    blocks.read_and_check_header()?;
+    Ok(blocks.header_bbox)
+}
+
+/// Bounds how many decoded blobs may sit in [ParallelFeatures]'s channel at once. The
+/// producer thread does not wait on it: it keeps reading raw blobs and queueing them for the
+/// worker pool, so raw blobs read ahead of decoding are not bounded by this.
+const PARALLEL_QUEUE_DEPTH_PER_THREAD: usize = 4;
+
+/// Drives [features_from_file_parallel]: a background thread reads raw blobs off `reader` in
+/// file order and hands each to a [rayon::ThreadPool] for decompression and parsing, while
+/// this struct receives `(blob index, decoded features)` pairs over a channel and reorders
+/// them back into file order before flattening to individual [Features](Feature).
+struct ParallelFeatures {
+    rx: mpsc::Receiver<(usize, Result<Vec<Feature>, Error>)>,
+    pending: HashMap<usize, Result<Vec<Feature>, Error>>,
+    next_index: usize,
+    current: std::vec::IntoIter<Feature>,
+    errored: bool,
+    _producer: thread::JoinHandle<()>,
+}
+
+impl ParallelFeatures {
+    fn new<R: io::Read + Send + 'static>(
+        reader: R,
+        num_threads: usize,
+        include_metadata: bool,
+        query_bbox: Option<[f32; 4]>,
+    ) -> Self {
+        let (tx, rx) = mpsc::sync_channel(num_threads.max(1) * PARALLEL_QUEUE_DEPTH_PER_THREAD);
+        let producer = thread::spawn(move || {
+            read_blocks_parallel(reader, num_threads, include_metadata, query_bbox, tx)
+        });
+        Self {
+            rx,
+            pending: HashMap::new(),
+            next_index: 0,
+            current: Vec::new().into_iter(),
+            errored: false,
+            _producer: producer,
+        }
+    }
+
+    /// Blocks until the blob at `self.next_index` has arrived, buffering any blobs that
+    /// complete out of order in `pending` in the meantime.
+    fn recv_next(&mut self) -> Option<Result<Vec<Feature>, Error>> {
+        loop {
+            if let Some(result) = self.pending.remove(&self.next_index) {
+                self.next_index += 1;
+                return Some(result);
+            }
+            match self.rx.recv() {
+                Ok((index, result)) => {
+                    self.pending.insert(index, result);
+                }
+                Err(_) => return None, // producer is done and has dropped its sender
+            }
+        }
+    }
+}
+
+impl Iterator for ParallelFeatures {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        loop {
+            if let Some(feature) = self.current.next() {
+                return Some(Ok(feature));
+            }
+            match self.recv_next() {
+                Some(Ok(features)) => self.current = features.into_iter(),
+                Some(Err(e)) => {
+                    self.errored = true;
+                    return Some(Err(e));
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+/// Single-threaded I/O loop backing [ParallelFeatures]: reads raw blobs off `reader` in file
+/// order and dispatches each to a rayon pool for decoding, sending `(index, result)` pairs
+/// back over `tx` as they complete.
+fn read_blocks_parallel<R: io::Read>(
+    reader: R,
+    num_threads: usize,
+    include_metadata: bool,
+    query_bbox: Option<[f32; 4]>,
+    tx: mpsc::SyncSender<(usize, Result<Vec<Feature>, Error>)>,
+) {
+    let pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads.max(1))
+        .build()
+    {
+        Ok(pool) => pool,
+        Err(e) => {
+            let _ = tx.send((0, Err(Error::Io(Arc::new(io::Error::other(e))))));
+            return;
+        }
+    };
+
+    let mut blocks = FileBlocks::new(reader);
+    let mut index = 0usize;
+
+    match blocks.read_and_check_header() {
+        Ok(true) => {}
+        Ok(false) => return, // empty file, no blocks at all
+        Err(e) => {
+            let _ = tx.send((index, Err(e)));
+            return;
+        }
+    }
+
+    pool.scope(|scope| loop {
+        let raw_blob = match blocks.try_read_raw_data_blob() {
+            Ok(Some(raw_blob)) => raw_blob,
+            Ok(None) => break, // no more OSMData blobs
+            Err(e) => {
+                let _ = tx.send((index, Err(e)));
+                break;
+            }
+        };
+
+        let tx = tx.clone();
+        let i = index;
+        scope.spawn(move |_| {
+            let result = decode_raw_data_blob(raw_blob, include_metadata, query_bbox);
+            let _ = tx.send((i, result));
+        });
+        index += 1;
+    });
+}
+
+/// Decompresses and parses a raw `OSMData` [fileformat::Blob] into its [Features](Feature) -
+/// the CPU-bound part of [FileBlocks::read_data], run on a rayon worker by
+/// [read_blocks_parallel] instead of inline.
+fn decode_raw_data_blob(
+    blob: fileformat::Blob,
+    include_metadata: bool,
+    query_bbox: Option<[f32; 4]>,
+) -> Result<Vec<Feature>, Error> {
+    let data = decompress_blob(blob)?;
+    let block = osmformat::PrimitiveBlock::parse_from_bytes(&data)?;
+    Ok(Block(block).features(include_metadata, query_bbox).collect())
 }
 
 /// File abstracts away a whole OSM PBF file, a file encoding multiple [blocks](osmformat::PrimitiveBlock).
@@ -79,16 +267,18 @@ struct File<R: io::Read>(R);
 impl<R: io::Read> File<R> {
     /// Returns an iterator over all [Blocks](Block) in this file.
     fn blocks(self) -> impl Iterator<Item = Result<Block, Error>> {
-        FileBlocks {
-            reader: self.0,
-            done: false,
-        }
+        FileBlocks::new(self.0)
     }
 
     /// Returns a flattened iterator over all [Features](Feature) from all
     /// [Groups](Group) from all [Blocks](Block) in this file.
-    fn features(self) -> impl Iterator<Item = Result<Feature, Error>> {
-        self.blocks().flat_map(block_result_features)
+    fn features(
+        self,
+        include_metadata: bool,
+        query_bbox: Option<[f32; 4]>,
+    ) -> impl Iterator<Item = Result<Feature, Error>> {
+        self.blocks()
+            .flat_map(move |r| block_result_features(r, include_metadata, query_bbox))
     }
 }
 
@@ -96,6 +286,22 @@ impl<R: io::Read> File<R> {
 struct FileBlocks<R: io::Read> {
     reader: R,
     done: bool,
+
+    /// Whether the leading `OSMHeader` blob has already been read and validated. A real
+    /// `.osm.pbf` file carries exactly one of these, followed by every `OSMData` blob in
+    /// the file, so [Iterator::next] only checks for it once rather than before every
+    /// block.
+    header_read: bool,
+
+    /// The most recently read `OSMHeader`'s [HeaderBBox](osmformat::HeaderBBox), if any -
+    /// see [header_bbox_from_file].
+    header_bbox: Option<[f32; 4]>,
+}
+
+impl<R: io::Read> FileBlocks<R> {
+    fn new(reader: R) -> Self {
+        FileBlocks { reader, done: false, header_read: false, header_bbox: None }
+    }
 }
 
 impl<R: io::Read> Iterator for FileBlocks<R> {
@@ -103,27 +309,38 @@ impl<R: io::Read> Iterator for FileBlocks<R> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.done {
-            None
-        } else {
-            let result = match self.read_and_check_header() {
-                Ok(true) => Some(self.read_data()),
-                Ok(false) => None,
-                Err(e) => Some(Err(e)),
-            };
-
-            self.done = match &result {
-                None | Some(Err(_)) => true,
-                Some(Ok(_)) => false,
-            };
+            return None;
+        }
 
-            result
+        if !self.header_read {
+            match self.read_and_check_header() {
+                Ok(true) => self.header_read = true,
+                Ok(false) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
         }
+
+        let result = match self.try_read_data() {
+            Ok(Some(block)) => Some(Ok(block)),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        };
+
+        self.done = matches!(result, None | Some(Err(_)));
+        result
     }
 }
 
 impl<R: io::Read> FileBlocks<R> {
-    /// Reads the next size + [fileformat::BlobHeader] + [fileformat::Blob] sequence,
-    /// expecting an `OSMHeader` block containing an [osmformat::HeaderBlock].
+    /// Reads the leading size + [fileformat::BlobHeader] + [fileformat::Blob] sequence,
+    /// expecting an `OSMHeader` block containing an [osmformat::HeaderBlock]. Only ever
+    /// called once per file - see [FileBlocks::header_read].
     ///
     /// Returns `Ok(true)` if a header block was successfully read and validated,
     /// `Ok(false)` on EOF, or an [Error] if anything bad has happened.
@@ -148,6 +365,7 @@ impl<R: io::Read> FileBlocks<R> {
         // 3. Read the OSMHeader blob
         let blob = self.read_blob(blob_header.datasize())?;
         let header = osmformat::HeaderBlock::parse_from_bytes(&blob)?;
+        self.header_bbox = convert_header_bbox(&header.bbox);
 
         // 3.1. Check required features
         let mut unknown_features = Vec::new();
@@ -166,17 +384,32 @@ impl<R: io::Read> FileBlocks<R> {
     }
 
     /// Reads the next size + [fileformat::BlobHeader] + [fileformat::Blob] sequence,
-    /// expecting an `OSMData` block containing an [Block] ([osmformat::PrimitiveBlock]).
-    fn read_data(&mut self) -> Result<Block, Error> {
+    /// expecting an `OSMData` block containing a [Block] ([osmformat::PrimitiveBlock]).
+    ///
+    /// Returns `Ok(None)` on a clean EOF (no more blobs), same as
+    /// [FileBlocks::try_read_raw_data_blob].
+    fn try_read_data(&mut self) -> Result<Option<Block>, Error> {
+        let Some(blob) = self.try_read_raw_data_blob()? else {
+            return Ok(None);
+        };
+        let data = decompress_blob(blob)?;
+        let block = osmformat::PrimitiveBlock::parse_from_bytes(&data)?;
+        Ok(Some(Block(block)))
+    }
+
+    /// Reads the next size + [fileformat::BlobHeader] + [fileformat::Blob] sequence,
+    /// expecting an `OSMData` block, but returns the raw (still compressed)
+    /// [fileformat::Blob] instead of decompressing and parsing it - used by
+    /// [features_from_file_parallel] to keep that CPU-bound work off the I/O thread.
+    ///
+    /// Returns `Ok(None)` on a clean EOF (no more blobs) - a real `.osm.pbf` file has no
+    /// trailer after its last `OSMData` blob, so running out of bytes here just means the
+    /// file has been fully consumed, not that anything went wrong.
+    fn try_read_raw_data_blob(&mut self) -> Result<Option<fileformat::Blob>, Error> {
         // 1. Read the BlobHeader size
         let blob_header_size = match self.read_blob_header_size()? {
             Some(size) => size,
-            None => {
-                return Err(Error::Io(Arc::new(io::Error::new(
-                    io::ErrorKind::UnexpectedEof,
-                    "expected BlobHeader for PrimitiveBlock, got EOF",
-                ))))
-            }
+            None => return Ok(None), // no more blobs
         };
 
         // 2. Read the BlobHeader
@@ -190,10 +423,8 @@ impl<R: io::Read> FileBlocks<R> {
             });
         }
 
-        // 3. Read the PrimitiveBlock blob
-        let blob = self.read_blob(blob_header.datasize())?;
-        let block = osmformat::PrimitiveBlock::parse_from_bytes(&blob)?;
-        Ok(Block(block))
+        // 3. Read the raw Blob
+        Ok(Some(self.read_raw_blob(blob_header.datasize())?))
     }
 
     /// Reads the next 4 bytes to read the size of the subsequent [fileformat::BlobHeader].
@@ -226,6 +457,11 @@ impl<R: io::Read> FileBlocks<R> {
 
     /// Reads the next [fileformat::Blob] and returns the decompressed contents of it.
     fn read_blob(&mut self, size: i32) -> Result<Vec<u8>, Error> {
+        decompress_blob(self.read_raw_blob(size)?)
+    }
+
+    /// Reads the next [fileformat::Blob] without decompressing its contents.
+    fn read_raw_blob(&mut self, size: i32) -> Result<fileformat::Blob, Error> {
         if size < 0 {
             return Err(Error::NegativeBlobHeaderSize);
         }
@@ -233,41 +469,263 @@ impl<R: io::Read> FileBlocks<R> {
         let mut buf = vec![0u8; size as usize];
         self.reader.read_exact(&mut buf)?;
 
-        let blob = fileformat::Blob::parse_from_bytes(&buf)?;
+        Ok(fileformat::Blob::parse_from_bytes(&buf)?)
+    }
+}
 
-        // FIXME: Don't blindly trust `blob.raw_size` for detecting too large blobs.
-        //        There should be a way to prevent too large allocations during decompression.
-        let blob_size = blob.raw_size() as u32;
-        if blob_size > MAX_BLOB_SIZE {
-            return Err(Error::BlobTooLarge(blob_size));
-        }
+/// The on-disk location of a single `OSMData` block's (still compressed) [fileformat::Blob],
+/// plus the id ranges of the objects it carries - built once by [IndexedFile::build] and
+/// replayed by [IndexedFile::read_block_at], or pre-filtered by [IndexedFile::blocks_containing].
+#[derive(Debug, Clone, Copy)]
+pub struct BlockLocation {
+    offset: u64,
+    length: u64,
+    node_ids: IdRange,
+    way_ids: IdRange,
+    relation_ids: IdRange,
+}
+
+/// The smallest closed `[min, max]` range covering a set of ids, used by [BlockLocation] as a
+/// cheap (over-approximate) pre-filter instead of recording every id a block carries.
+#[derive(Debug, Clone, Copy)]
+struct IdRange {
+    min: i64,
+    max: i64,
+}
+
+impl IdRange {
+    const EMPTY: IdRange = IdRange { min: i64::MAX, max: i64::MIN };
+
+    fn contains(&self, id: i64) -> bool {
+        id >= self.min && id <= self.max
+    }
+
+    fn including(mut self, id: i64) -> Self {
+        self.min = self.min.min(id);
+        self.max = self.max.max(id);
+        self
+    }
+}
 
-        match blob
-            .data
-            .expect("Blob.data must not be None after parse_from_bytes")
-        {
-            fileformat::blob::Data::Raw(data) => Ok(data),
+/// Scans a block's standalone and dense-encoded nodes, ways, and relations for their id
+/// ranges, without building a single [Feature] - the same lightweight pass [block_bbox] does
+/// for coordinates.
+fn id_ranges(block: &osmformat::PrimitiveBlock) -> (IdRange, IdRange, IdRange) {
+    let mut node_ids = IdRange::EMPTY;
+    let mut way_ids = IdRange::EMPTY;
+    let mut relation_ids = IdRange::EMPTY;
+
+    for group in &block.primitivegroup {
+        for node in &group.nodes {
+            node_ids = node_ids.including(node.id());
+        }
 
-            fileformat::blob::Data::ZlibData(data) => {
-                let mut d = flate2::read::ZlibDecoder::new(&data[..]);
-                let mut decompressed = Vec::with_capacity(blob_size as usize);
-                d.read_to_end(&mut decompressed)?;
-                Ok(decompressed)
+        if let Some(dense) = group.dense.as_ref() {
+            let mut acc = 0i64;
+            for &delta in &dense.id {
+                acc += delta;
+                node_ids = node_ids.including(acc);
             }
+        }
+
+        for way in &group.ways {
+            way_ids = way_ids.including(way.id());
+        }
 
-            fileformat::blob::Data::LzmaData(_) => Err(Error::UnsupportedCompression("lzma")),
+        for relation in &group.relations {
+            relation_ids = relation_ids.including(relation.id());
+        }
+    }
 
-            fileformat::blob::Data::OBSOLETEBzip2Data(data) => {
-                let mut d = bzip2::read::BzDecoder::new(&data[..]);
-                let mut decompressed = Vec::with_capacity(blob_size as usize);
-                d.read_to_end(&mut decompressed)?;
-                Ok(decompressed)
+    (node_ids, way_ids, relation_ids)
+}
+
+/// A [File] opened over a [Seek]-able reader, indexed up front so individual blocks can be
+/// re-read on demand instead of only streamed in order - for pipelines that need a second
+/// pass over specific objects (e.g. resolving way/relation references) without holding the
+/// whole file's features in memory.
+pub struct IndexedFile<R: io::Read + io::Seek> {
+    reader: R,
+    index: Vec<BlockLocation>,
+}
+
+impl<R: io::Read + io::Seek> IndexedFile<R> {
+    /// Scans `reader` once end-to-end, recording every `OSMData` block's [BlockLocation]
+    /// without retaining its decoded [Features](Feature).
+    pub fn build(reader: R) -> Result<Self, Error> {
+        let mut blocks = FileBlocks::new(reader);
+        let mut index = Vec::new();
+
+        match blocks.read_and_check_header() {
+            Ok(true) => {}
+            Ok(false) => return Ok(Self { reader: blocks.reader, index }), // empty file
+            Err(e) => return Err(e),
+        }
+
+        loop {
+            // Mirror FileBlocks::try_read_raw_data_blob, but note the blob's own offset first.
+            let blob_header_size = match blocks.read_blob_header_size()? {
+                Some(size) => size,
+                None => break, // no more OSMData blobs
+            };
+            let blob_header = blocks.read_blob_header(blob_header_size)?;
+            if blob_header.type_() != "OSMData" {
+                return Err(Error::UnexpectedBlobHeaderType {
+                    got: blob_header.type_.unwrap_or_default(),
+                    expected: "OSMData",
+                });
             }
 
-            fileformat::blob::Data::Lz4Data(_) => Err(Error::UnsupportedCompression("lz4")),
+            let offset = blocks.reader.stream_position()?;
+            let length = blob_header.datasize() as u64;
+            let raw_blob = blocks.read_raw_blob(blob_header.datasize())?;
+
+            let data = decompress_blob(raw_blob)?;
+            let block = osmformat::PrimitiveBlock::parse_from_bytes(&data)?;
+            let (node_ids, way_ids, relation_ids) = id_ranges(&block);
+
+            index.push(BlockLocation {
+                offset,
+                length,
+                node_ids,
+                way_ids,
+                relation_ids,
+            });
+        }
+
+        Ok(Self { reader: blocks.reader, index })
+    }
+
+    /// Returns every indexed [BlockLocation]. Callers needing a specific subset should prefer
+    /// [Self::blocks_containing].
+    pub fn locations(&self) -> &[BlockLocation] {
+        &self.index
+    }
+
+    /// Decodes a single block at the given [BlockLocation] on demand, seeking to it directly
+    /// rather than reading anything before it.
+    pub fn read_block_at(&mut self, location: BlockLocation) -> Result<Block, Error> {
+        self.reader.seek(io::SeekFrom::Start(location.offset))?;
+        let mut buf = vec![0u8; location.length as usize];
+        self.reader.read_exact(&mut buf)?;
+        let blob = fileformat::Blob::parse_from_bytes(&buf)?;
+        let data = decompress_blob(blob)?;
+        let block = osmformat::PrimitiveBlock::parse_from_bytes(&data)?;
+        Ok(Block(block))
+    }
+
+    /// Returns every indexed [BlockLocation] whose node/way/relation id range could contain
+    /// any of `ids` - a cheap pre-filter, not an exact membership test, since [IdRange] only
+    /// tracks a block's min/max id per object type, not the exact set.
+    pub fn blocks_containing(&self, ids: &[i64]) -> Vec<BlockLocation> {
+        self.index
+            .iter()
+            .copied()
+            .filter(|loc| {
+                ids.iter().any(|&id| {
+                    loc.node_ids.contains(id)
+                        || loc.way_ids.contains(id)
+                        || loc.relation_ids.contains(id)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Decompresses a parsed [fileformat::Blob] according to its compression variant.
+fn decompress_blob(blob: fileformat::Blob) -> Result<Vec<u8>, Error> {
+    // `blob.raw_size` is attacker-controlled and only used here as an allocation
+    // hint - the real cap is enforced below by `read_bounded` regardless of what
+    // this declares, since a blob can under-report `raw_size` and still expand past
+    // `MAX_BLOB_SIZE` during inflation.
+    let blob_size = blob.raw_size() as u32;
+    if blob_size > MAX_BLOB_SIZE {
+        return Err(Error::BlobTooLarge(blob_size));
+    }
+
+    match blob
+        .data
+        .expect("Blob.data must not be None after parse_from_bytes")
+    {
+        fileformat::blob::Data::Raw(data) => Ok(data),
+
+        fileformat::blob::Data::ZlibData(data) => {
+            read_bounded(flate2::read::ZlibDecoder::new(&data[..]), blob_size)
+        }
+
+        fileformat::blob::Data::LzmaData(_) => Err(Error::UnsupportedCompression("lzma")),
+
+        fileformat::blob::Data::OBSOLETEBzip2Data(data) => {
+            read_bounded(bzip2::read::BzDecoder::new(&data[..]), blob_size)
+        }
+
+        fileformat::blob::Data::Lz4Data(data) => {
+            read_bounded(lz4_flex::frame::FrameDecoder::new(&data[..]), blob_size)
+        }
+
+        fileformat::blob::Data::ZstdData(data) => {
+            read_bounded(zstd::stream::Decoder::new(&data[..])?, blob_size)
+        }
+    }
+}
+
+/// Reads `d` to the end, capped at [MAX_BLOB_SIZE] regardless of `size_hint` (only used
+/// to pre-size the output buffer) - protects against a decompression bomb: a blob that
+/// under-reports its decompressed size in `raw_size` but keeps inflating well past it.
+fn read_bounded<D: Read>(d: D, size_hint: u32) -> Result<Vec<u8>, Error> {
+    let mut decompressed = Vec::with_capacity(size_hint as usize);
+    match BoundedReader::new(d, MAX_BLOB_SIZE as usize).read_to_end(&mut decompressed) {
+        Ok(_) => Ok(decompressed),
+        Err(e) if e.get_ref().is_some_and(|i| i.is::<BlobTooLargeMarker>()) => {
+            Err(Error::BlobTooLarge(MAX_BLOB_SIZE))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Sentinel stashed in the [io::Error] returned by [BoundedReader] once its cap is hit,
+/// so [read_bounded] can tell "decompressor ran past the cap" apart from an ordinary I/O
+/// failure.
+#[derive(Debug)]
+struct BlobTooLargeMarker;
+
+impl std::fmt::Display for BlobTooLargeMarker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "decompressed blob exceeds MAX_BLOB_SIZE")
+    }
+}
+
+impl std::error::Error for BlobTooLargeMarker {}
+
+/// Wraps a decompressing [Read] and enforces a hard cap on the number of bytes it may
+/// produce, independent of any size the compressed data itself declares.
+struct BoundedReader<D: Read> {
+    inner: D,
+    remaining: usize,
+}
 
-            fileformat::blob::Data::ZstdData(_) => Err(Error::UnsupportedCompression("zstd")),
+impl<D: Read> BoundedReader<D> {
+    fn new(inner: D, limit: usize) -> Self {
+        Self { inner, remaining: limit }
+    }
+}
+
+impl<D: Read> Read for BoundedReader<D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            // The cap was reached on a previous call - error out only if the decoder
+            // still has more to give, rather than silently truncating the output.
+            return if self.inner.read(&mut [0u8; 1])? == 0 {
+                Ok(0)
+            } else {
+                Err(io::Error::new(io::ErrorKind::Other, BlobTooLargeMarker))
+            };
         }
+
+        let cap = buf.len().min(self.remaining);
+        let n = self.inner.read(&mut buf[..cap])?;
+        self.remaining -= n;
+        Ok(n)
     }
 }
 
@@ -297,31 +755,57 @@ impl<I: Iterator<Item = Feature>> Iterator for BlockResultFeatureIterator<I> {
 
 fn block_result_features(
     block_result: Result<Block, Error>,
-) -> BlockResultFeatureIterator<impl Iterator<Item = Feature>> {
+    include_metadata: bool,
+    query_bbox: Option<[f32; 4]>,
+) -> BlockResultFeatureIterator<Box<dyn Iterator<Item = Feature>>> {
     match block_result {
-        Ok(block) => BlockResultFeatureIterator::Iterating(block.features()),
+        Ok(block) => {
+            BlockResultFeatureIterator::Iterating(block.features(include_metadata, query_bbox))
+        }
         Err(e) => BlockResultFeatureIterator::Done(Some(e)),
     }
 }
 
 /// Block abstracts away an [osmformat::PrimitiveBlock] into a friendly interface.
-struct Block(osmformat::PrimitiveBlock);
+pub struct Block(osmformat::PrimitiveBlock);
 
 impl Block {
-    /// Returns an iterator over all [Groups](Group) in this block.
-    fn groups(self) -> impl Iterator<Item = Group> {
+    /// Returns an iterator over all [Groups](Group) in this block. `include_metadata` is
+    /// forwarded to every [Group] since whether to decode `Info`/`DenseInfo` is a per-file,
+    /// not per-group, decision.
+    fn groups(self, include_metadata: bool) -> impl Iterator<Item = Group> {
         let coordinate_converter = self.build_coordinate_converter();
         let string_table = Rc::new(self.build_string_table());
+        let metadata_options = MetadataOptions {
+            include: include_metadata,
+            date_granularity: self.0.date_granularity() as i64,
+        };
         self.0.primitivegroup.into_iter().map(move |g| Group {
             primitive_group: g,
-            coordinate_converter: coordinate_converter,
+            coordinate_converter,
             string_table: string_table.clone(),
+            metadata_options,
         })
     }
 
-    /// Returns a flattened iterator over all [Features](Feature) from all [Groups](Group) in this block.
-    fn features(self) -> impl Iterator<Item = Feature> {
-        self.groups().flat_map(|g| g.features())
+    /// Returns a flattened iterator over all [Features](Feature) from all [Groups](Group) in
+    /// this block. If `query_bbox` is given and this block's [computed bbox](block_bbox)
+    /// doesn't intersect it, the whole block is skipped without building a single [Feature] -
+    /// including any way/relation it carries, since neither has its own coordinates to check.
+    pub fn features(
+        self,
+        include_metadata: bool,
+        query_bbox: Option<[f32; 4]>,
+    ) -> Box<dyn Iterator<Item = Feature>> {
+        if let Some(query_bbox) = query_bbox {
+            let coordinate_converter = self.build_coordinate_converter();
+            if let Some(bbox) = block_bbox(&self.0, &coordinate_converter) {
+                if !bbox_intersects(bbox, query_bbox) {
+                    return Box::new(std::iter::empty());
+                }
+            }
+        }
+        Box::new(self.groups(include_metadata).flat_map(|g| g.features()))
     }
 
     /// Converts the [osmformat::StringTable] into a simpler `Vec<String>`.
@@ -344,53 +828,90 @@ impl Block {
     }
 }
 
+/// Controls whether [Group]'s feature constructors decode each object's [Metadata], and if
+/// so, the `date_granularity` (block-wide, not per-group) needed to scale `DenseInfo`/`Info`
+/// timestamps into milliseconds.
+#[derive(Clone, Copy)]
+struct MetadataOptions {
+    include: bool,
+    date_granularity: i64,
+}
+
 /// Group abstracts away an [osmformat::PrimitiveGroup] into a friendly interface.
 struct Group {
     primitive_group: osmformat::PrimitiveGroup,
     coordinate_converter: CoordinateConverter,
     string_table: StringTable,
+    metadata_options: MetadataOptions,
 }
 
 impl Group {
     /// Returns a flattened iterator over all [Features](Feature) in this group.
     fn features(self) -> impl Iterator<Item = Feature> {
-        let nodes =
-            Self::nodes(self.primitive_group.nodes, self.coordinate_converter).map(Feature::Node);
+        let nodes = Self::nodes(
+            self.primitive_group.nodes,
+            self.coordinate_converter,
+            self.string_table.clone(),
+            self.metadata_options,
+        )
+        .map(|(n, tags, meta)| Feature::Node(n, tags, meta));
 
         let dense_nodes = Self::dense_nodes(
             self.primitive_group.dense.unwrap_or_default(),
             self.coordinate_converter,
+            self.string_table.clone(),
+            self.metadata_options,
         )
-        .map(Feature::Node);
+        .map(|(n, tags, meta)| Feature::Node(n, tags, meta));
 
-        let ways =
-            Self::ways(self.primitive_group.ways, self.string_table.clone()).map(Feature::Way);
+        let ways = Self::ways(
+            self.primitive_group.ways,
+            self.string_table.clone(),
+            self.metadata_options,
+        )
+        .map(Feature::Way);
 
-        let relations = Self::relations(self.primitive_group.relations, self.string_table)
-            .map(Feature::Relation);
+        let relations = Self::relations(
+            self.primitive_group.relations,
+            self.string_table,
+            self.metadata_options,
+        )
+        .map(Feature::Relation);
 
         nodes.chain(dense_nodes).chain(ways).chain(relations)
     }
 
-    /// Returns an iterator over all standard (non-dense-encoded) [nodes](Node) from a moved
-    /// vector of [raw nodes](osmformat::Node).
+    /// Returns an iterator over all standard (non-dense-encoded) [nodes](Node), paired with
+    /// their tags and [Metadata], from a moved vector of [raw nodes](osmformat::Node).
     fn nodes(
         raw_nodes: Vec<osmformat::Node>,
         coordinate_converter: CoordinateConverter,
-    ) -> impl Iterator<Item = Node> {
-        raw_nodes.into_iter().map(move |node| Node {
-            id: node.id(),
-            osm_id: node.id(),
-            lat: coordinate_converter.convert_lat(node.lat()),
-            lon: coordinate_converter.convert_lon(node.lon()),
+        string_table: StringTable,
+        metadata_options: MetadataOptions,
+    ) -> impl Iterator<Item = (Node, HashMap<String, String>, Option<Metadata>)> {
+        raw_nodes.into_iter().map(move |node| {
+            let tags = collect_tags(&node.keys, &node.vals, &string_table);
+            let meta = metadata_options
+                .include
+                .then(|| build_metadata(&node.info, &string_table, metadata_options.date_granularity));
+            let n = Node {
+                id: node.id(),
+                osm_id: node.id(),
+                lat: coordinate_converter.convert_lat(node.lat()),
+                lon: coordinate_converter.convert_lon(node.lon()),
+            };
+            (n, tags, meta)
         })
     }
 
-    /// Returns an iterator over all dense-encoded [nodes](Node) from a moved [raw dense nodes](osmformat::DenseNodes).
+    /// Returns an iterator over all dense-encoded [nodes](Node), paired with their tags and
+    /// [Metadata], from a moved [raw dense nodes](osmformat::DenseNodes).
     fn dense_nodes(
         raw_dense_nodes: osmformat::DenseNodes,
         coordinate_converter: CoordinateConverter,
-    ) -> impl Iterator<Item = Node> {
+        string_table: StringTable,
+        metadata_options: MetadataOptions,
+    ) -> impl Iterator<Item = (Node, HashMap<String, String>, Option<Metadata>)> {
         let ids = raw_dense_nodes.id.into_iter().scan(0, |acc, delta| {
             *acc += delta;
             Some(*acc)
@@ -406,20 +927,37 @@ impl Group {
             Some(coordinate_converter.convert_lon(*acc))
         });
 
-        ids.zip(lats.zip(lons)).map(|(id, (lat, lon))| Node {
-            id,
-            osm_id: id,
-            lat,
-            lon,
-        })
+        let metas = dense_metadata(
+            raw_dense_nodes.denseinfo.unwrap_or_default(),
+            string_table.clone(),
+            metadata_options,
+        );
+
+        let tags = dense_tag_runs(raw_dense_nodes.keys_vals)
+            .map(move |(keys, vals)| collect_tags(&keys, &vals, &string_table));
+
+        ids.zip(lats.zip(lons))
+            .zip(tags)
+            .zip(metas)
+            .map(|(((id, (lat, lon)), tags), meta)| (Node { id, osm_id: id, lat, lon }, tags, meta))
     }
 
     /// Returns an iterator over all [ways](Way) from a moved vector of [raw ways](osmformat::Way).
-    fn ways(raw_ways: Vec<osmformat::Way>, string_table: StringTable) -> impl Iterator<Item = Way> {
-        raw_ways.into_iter().map(move |way| Way {
-            id: way.id(),
-            nodes: collect_way_nodes(&way.refs),
-            tags: collect_tags(&way.keys, &way.vals, &string_table),
+    fn ways(
+        raw_ways: Vec<osmformat::Way>,
+        string_table: StringTable,
+        metadata_options: MetadataOptions,
+    ) -> impl Iterator<Item = Way> {
+        raw_ways.into_iter().map(move |way| {
+            let meta = metadata_options
+                .include
+                .then(|| build_metadata(&way.info, &string_table, metadata_options.date_granularity));
+            Way {
+                id: way.id(),
+                nodes: collect_way_nodes(&way.refs),
+                tags: collect_tags(&way.keys, &way.vals, &string_table),
+                meta,
+            }
         })
     }
 
@@ -427,16 +965,23 @@ impl Group {
     fn relations(
         raw_relations: Vec<osmformat::Relation>,
         string_table: StringTable,
+        metadata_options: MetadataOptions,
     ) -> impl Iterator<Item = Relation> {
-        raw_relations.into_iter().map(move |relation| Relation {
-            id: relation.id(),
-            members: collect_relation_members(
-                &relation.memids,
-                &relation.roles_sid,
-                &relation.types,
-                &string_table,
-            ),
-            tags: collect_tags(&relation.keys, &relation.vals, &string_table),
+        raw_relations.into_iter().map(move |relation| {
+            let meta = metadata_options.include.then(|| {
+                build_metadata(&relation.info, &string_table, metadata_options.date_granularity)
+            });
+            Relation {
+                id: relation.id(),
+                members: collect_relation_members(
+                    &relation.memids,
+                    &relation.roles_sid,
+                    &relation.types,
+                    &string_table,
+                ),
+                tags: collect_tags(&relation.keys, &relation.vals, &string_table),
+                meta,
+            }
         })
     }
 }
@@ -459,6 +1004,73 @@ impl CoordinateConverter {
     }
 }
 
+/// Converts a `HeaderBBox`, if present, into `[left, bottom, right, top]` degrees - the same
+/// layout [Options::bbox](super::Options::bbox) uses. `HeaderBBox`'s coordinates are always
+/// nanodegrees, regardless of a block's own `granularity`.
+fn convert_header_bbox(bbox: &protobuf::MessageField<osmformat::HeaderBBox>) -> Option<[f32; 4]> {
+    bbox.as_ref().map(|b| {
+        [
+            b.left() as f32 * 1e-9,
+            b.bottom() as f32 * 1e-9,
+            b.right() as f32 * 1e-9,
+            b.top() as f32 * 1e-9,
+        ]
+    })
+}
+
+/// Returns `true` if the `[left, bottom, right, top]` boxes `a` and `b` overlap (touching
+/// edges count as overlapping).
+fn bbox_intersects(a: [f32; 4], b: [f32; 4]) -> bool {
+    let [a_left, a_bottom, a_right, a_top] = a;
+    let [b_left, b_bottom, b_right, b_top] = b;
+    a_left <= b_right && b_left <= a_right && a_bottom <= b_top && b_bottom <= a_top
+}
+
+/// Computes the bounding box actually spanned by a block's nodes. [osmformat::PrimitiveBlock]
+/// carries no such field itself, so this scans every standalone/dense node's coordinates -
+/// the same work [Group::nodes]/[Group::dense_nodes] do, minus tag/metadata decoding - and
+/// returns `None` if the block has no nodes at all (e.g. a block of only ways/relations).
+fn block_bbox(
+    block: &osmformat::PrimitiveBlock,
+    coordinate_converter: &CoordinateConverter,
+) -> Option<[f32; 4]> {
+    let mut min_lat = f32::INFINITY;
+    let mut min_lon = f32::INFINITY;
+    let mut max_lat = f32::NEG_INFINITY;
+    let mut max_lon = f32::NEG_INFINITY;
+    let mut any = false;
+
+    for group in &block.primitivegroup {
+        for node in &group.nodes {
+            any = true;
+            let lat = coordinate_converter.convert_lat(node.lat());
+            let lon = coordinate_converter.convert_lon(node.lon());
+            min_lat = min_lat.min(lat);
+            max_lat = max_lat.max(lat);
+            min_lon = min_lon.min(lon);
+            max_lon = max_lon.max(lon);
+        }
+
+        if let Some(dense) = group.dense.as_ref() {
+            let mut lat_acc = 0i64;
+            let mut lon_acc = 0i64;
+            for (&delta_lat, &delta_lon) in dense.lat.iter().zip(dense.lon.iter()) {
+                lat_acc += delta_lat;
+                lon_acc += delta_lon;
+                any = true;
+                let lat = coordinate_converter.convert_lat(lat_acc);
+                let lon = coordinate_converter.convert_lon(lon_acc);
+                min_lat = min_lat.min(lat);
+                max_lat = max_lat.max(lat);
+                min_lon = min_lon.min(lon);
+                max_lon = max_lon.max(lon);
+            }
+        }
+    }
+
+    any.then_some([min_lon, min_lat, max_lon, max_lat])
+}
+
 fn collect_tags(keys: &[u32], values: &[u32], string_table: &[String]) -> HashMap<String, String> {
     keys.iter()
         .zip(values.iter())
@@ -471,6 +1083,98 @@ fn collect_tags(keys: &[u32], values: &[u32], string_table: &[String]) -> HashMa
         .collect()
 }
 
+/// Builds a [Metadata] from a standard (non-dense) object's `Info`, scaling its timestamp by
+/// `date_granularity`. An absent `Info` - or absent fields within it - yields [Metadata]'s
+/// documented defaults, since `osmformat::Info` fields carry the same defaults themselves.
+fn build_metadata(
+    info: &protobuf::MessageField<osmformat::Info>,
+    string_table: &[String],
+    date_granularity: i64,
+) -> Metadata {
+    let info = info.as_ref();
+    Metadata {
+        version: info.map_or(-1, |i| i.version()),
+        timestamp: info.map_or(0, |i| i.timestamp()) * date_granularity,
+        changeset: info.map_or(0, |i| i.changeset()),
+        uid: info.map_or(0, |i| i.uid()),
+        user: info.map_or(String::new(), |i| get_string(string_table, i.user_sid() as u32)),
+    }
+}
+
+/// Lazily decodes `DenseInfo`'s parallel, delta-coded `timestamp`/`changeset`/`uid`/`user_sid`
+/// arrays (`version` is not delta-coded) into one [Metadata] per node, when `metadata_options`
+/// asks for it. Mirrors [dense_tag_runs]'s "run out, keep yielding forever" behavior so zipping
+/// against the (possibly longer) id/lat/lon iterators never panics: when metadata wasn't
+/// requested, this yields `None` forever; when it was but a file omits `DenseInfo` (or its
+/// arrays run out), this yields [Metadata] with its documented defaults.
+fn dense_metadata(
+    raw: osmformat::DenseInfo,
+    string_table: StringTable,
+    metadata_options: MetadataOptions,
+) -> impl Iterator<Item = Option<Metadata>> {
+    let include = metadata_options.include;
+    let date_granularity = metadata_options.date_granularity;
+
+    let mut versions = raw.version.into_iter();
+    let mut timestamps = raw.timestamp.into_iter().scan(0i64, |acc, delta| {
+        *acc += delta;
+        Some(*acc)
+    });
+    let mut changesets = raw.changeset.into_iter().scan(0i64, |acc, delta| {
+        *acc += delta;
+        Some(*acc)
+    });
+    let mut uids = raw.uid.into_iter().scan(0i32, |acc, delta| {
+        *acc += delta;
+        Some(*acc)
+    });
+    let mut user_sids = raw.user_sid.into_iter().scan(0i32, |acc, delta| {
+        *acc += delta;
+        Some(*acc)
+    });
+
+    std::iter::from_fn(move || {
+        if !include {
+            return Some(None);
+        }
+        Some(Some(Metadata {
+            version: versions.next().unwrap_or(-1),
+            timestamp: timestamps.next().unwrap_or(0) * date_granularity,
+            changeset: changesets.next().unwrap_or(0),
+            uid: uids.next().unwrap_or(0),
+            user: get_string(&string_table, user_sids.next().unwrap_or(0) as u32),
+        }))
+    })
+}
+
+/// Lazily splits DenseNodes' flat `keys_vals` into one `(keys, vals)` run per node: each
+/// node's run is a sequence of `key_idx, val_idx` pairs terminated by a single `0`.
+///
+/// An empty `keys_vals` means every node is untagged, and once the array is exhausted -
+/// including by a missing trailing terminator - this keeps yielding empty runs forever
+/// rather than stopping, so zipping it against the (possibly longer) id/lat/lon iterators
+/// never panics and simply leaves the remaining nodes untagged.
+fn dense_tag_runs(keys_vals: Vec<i32>) -> impl Iterator<Item = (Vec<u32>, Vec<u32>)> {
+    let mut cursor = keys_vals.into_iter();
+    std::iter::from_fn(move || {
+        let mut keys = Vec::new();
+        let mut vals = Vec::new();
+        loop {
+            match cursor.next() {
+                None | Some(0) => break,
+                Some(key_idx) => match cursor.next() {
+                    Some(val_idx) => {
+                        keys.push(key_idx as u32);
+                        vals.push(val_idx as u32);
+                    }
+                    None => break,
+                },
+            }
+        }
+        Some((keys, vals))
+    })
+}
+
 fn collect_way_nodes(ref_deltas: &[i64]) -> Vec<i64> {
     ref_deltas
         .iter()
@@ -510,3 +1214,158 @@ fn collect_relation_members(
 fn get_string(table: &[String], idx: u32) -> String {
     table.get(idx as usize).cloned().unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Serializes a single size-prefixed ([fileformat::BlobHeader], [fileformat::Blob]) pair
+    /// holding `payload` as an uncompressed `Blob.raw` - the same on-disk shape [FileBlocks]
+    /// reads back.
+    fn write_blob(buf: &mut Vec<u8>, type_: &str, payload: Vec<u8>) {
+        let blob = fileformat::Blob {
+            raw_size: Some(payload.len() as i32),
+            data: Some(fileformat::blob::Data::Raw(payload)),
+            ..Default::default()
+        };
+        let blob_bytes = blob.write_to_bytes().unwrap();
+
+        let header = fileformat::BlobHeader {
+            type_: Some(type_.to_string()),
+            datasize: Some(blob_bytes.len() as i32),
+            ..Default::default()
+        };
+        let header_bytes = header.write_to_bytes().unwrap();
+
+        buf.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&header_bytes);
+        buf.extend_from_slice(&blob_bytes);
+    }
+
+    /// Builds a minimal but realistic `.osm.pbf` byte stream: one `OSMHeader` blob followed
+    /// by `data_block_count` empty `OSMData` blobs - the shape every real extract has, and
+    /// the one the old [FileBlocks] iteration couldn't get past the first block of.
+    fn pbf_fixture(data_block_count: usize) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        let header_block = osmformat::HeaderBlock {
+            required_features: vec!["OsmSchema-V0.6".to_string()],
+            ..Default::default()
+        };
+        write_blob(&mut buf, "OSMHeader", header_block.write_to_bytes().unwrap());
+
+        let data_bytes = osmformat::PrimitiveBlock::default().write_to_bytes().unwrap();
+        for _ in 0..data_block_count {
+            write_blob(&mut buf, "OSMData", data_bytes.clone());
+        }
+
+        buf
+    }
+
+    #[test]
+    fn single_data_block_is_read() {
+        let blocks: Result<Vec<Block>, Error> =
+            File(Cursor::new(pbf_fixture(1))).blocks().collect();
+        assert_eq!(blocks.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn every_data_block_is_read() {
+        // Regression test: FileBlocks::next used to re-validate an OSMHeader blob before
+        // every block, so only the first of several OSMData blocks was ever read.
+        let blocks: Result<Vec<Block>, Error> =
+            File(Cursor::new(pbf_fixture(3))).blocks().collect();
+        assert_eq!(blocks.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn parallel_reader_reads_every_block() {
+        // read_blocks_parallel had the same once-per-block header-check bug as
+        // FileBlocks::next, so only the first dispatched blob ever decoded.
+        let features: Result<Vec<Feature>, Error> =
+            features_from_file_parallel(Cursor::new(pbf_fixture(4)), 2, false, None).collect();
+        assert_eq!(features.unwrap().len(), 0); // blocks are empty, but all 4 must be read
+    }
+
+    #[test]
+    fn indexed_file_indexes_every_block() {
+        let mut indexed = IndexedFile::build(Cursor::new(pbf_fixture(3))).unwrap();
+        let locations = indexed.locations().to_vec();
+        assert_eq!(locations.len(), 3);
+
+        for location in locations {
+            indexed.read_block_at(location).unwrap();
+        }
+    }
+
+    /// Builds a block carrying a single standalone node at `(lat, lon)` degrees, with
+    /// `granularity`/`lat_offset`/`lon_offset` left at their nanodegree-identity defaults so
+    /// the raw coordinates are just `degrees * 1e9`.
+    fn block_with_node(id: i64, lat: f64, lon: f64) -> Vec<u8> {
+        let node = osmformat::Node {
+            id: Some(id),
+            lat: Some((lat * 1e9) as i64),
+            lon: Some((lon * 1e9) as i64),
+            ..Default::default()
+        };
+        let group = osmformat::PrimitiveGroup { nodes: vec![node], ..Default::default() };
+        osmformat::PrimitiveBlock {
+            primitivegroup: vec![group],
+            granularity: Some(1),
+            lat_offset: Some(0),
+            lon_offset: Some(0),
+            ..Default::default()
+        }
+        .write_to_bytes()
+        .unwrap()
+    }
+
+    #[test]
+    fn header_bbox_from_file_ignores_data_blocks() {
+        // header_bbox_from_file only reads the leading blob, but shares FileBlocks with
+        // the iterators that didn't - exercise it alongside a multi-block file to pin down
+        // that the HeaderBBox is still the one read, not whatever the second block implies.
+        let mut buf = Vec::new();
+        let header_block = osmformat::HeaderBlock {
+            bbox: protobuf::MessageField::some(osmformat::HeaderBBox {
+                left: Some(0),
+                bottom: Some(0),
+                right: Some(10_000_000_000),
+                top: Some(10_000_000_000),
+                ..Default::default()
+            }),
+            required_features: vec!["OsmSchema-V0.6".to_string()],
+            ..Default::default()
+        };
+        write_blob(&mut buf, "OSMHeader", header_block.write_to_bytes().unwrap());
+        write_blob(&mut buf, "OSMData", block_with_node(1, 1.0, 1.0));
+        write_blob(&mut buf, "OSMData", block_with_node(2, 50.0, 50.0));
+
+        let bbox = header_bbox_from_file(Cursor::new(buf)).unwrap();
+        assert_eq!(bbox, Some([0.0, 0.0, 10.0, 10.0]));
+    }
+
+    #[test]
+    fn query_bbox_skips_out_of_range_blocks() {
+        let mut buf = Vec::new();
+        let header_block = osmformat::HeaderBlock {
+            required_features: vec!["OsmSchema-V0.6".to_string()],
+            ..Default::default()
+        };
+        write_blob(&mut buf, "OSMHeader", header_block.write_to_bytes().unwrap());
+        write_blob(&mut buf, "OSMData", block_with_node(1, 1.0, 1.0));
+        write_blob(&mut buf, "OSMData", block_with_node(2, 50.0, 50.0));
+
+        // Before the FileBlocks fix, this query never got to see the second block at all,
+        // so a passing skip here didn't prove anything - it just never ran.
+        let query_bbox = [0.0, 0.0, 10.0, 10.0];
+        let features: Vec<Feature> = File(Cursor::new(buf))
+            .features(false, Some(query_bbox))
+            .collect::<Result<Vec<_>, Error>>()
+            .unwrap();
+
+        assert_eq!(features.len(), 1);
+        assert!(matches!(&features[0], Feature::Node(n, _, _) if n.id == 1));
+    }
+}
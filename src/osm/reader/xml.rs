@@ -91,7 +91,9 @@ impl<P: Parser> Iterator for Reader<P> {
                 quick_xml::events::Event::Empty(start) => {
                     match start.local_name().as_ref() {
                         b"node" => match parse_node(start) {
-                            Some(n) => return Some(Ok(model::Feature::Node(n))),
+                            Some(n) => {
+                                return Some(Ok(model::Feature::Node(n, HashMap::default(), None)))
+                            }
                             None => {}
                         },
                         // "way" or "relation" can't be self-closing
@@ -121,7 +123,9 @@ impl<P: Parser> Iterator for Reader<P> {
                 }
 
                 quick_xml::events::Event::Start(start) => match start.local_name().as_ref() {
-                    b"node" => f = parse_node(start).map(|n| model::Feature::Node(n)),
+                    b"node" => {
+                        f = parse_node(start).map(|n| model::Feature::Node(n, HashMap::default(), None))
+                    }
                     b"way" => f = parse_way(start).map(|w| model::Feature::Way(w)),
                     b"relation" => f = parse_relation(start).map(|r| model::Feature::Relation(r)),
                     // "tag", "nd" and "member" must be self-closing
@@ -210,6 +214,7 @@ fn parse_way(start: quick_xml::events::BytesStart<'_>) -> Option<model::Way> {
             id: id,
             nodes: Vec::default(),
             tags: HashMap::default(),
+            meta: None,
         })
     } else {
         None
@@ -234,6 +239,7 @@ fn parse_relation(start: quick_xml::events::BytesStart<'_>) -> Option<model::Rel
             id: id,
             members: Vec::default(),
             tags: HashMap::default(),
+            meta: None,
         })
     } else {
         None
@@ -318,7 +324,7 @@ fn parse_feature_type(s: &[u8]) -> Option<model::FeatureType> {
 fn feature_tags<'a>(f: &'a mut Option<model::Feature>) -> Option<&'a mut HashMap<String, String>> {
     match f {
         None => None,
-        Some(model::Feature::Node(_)) => None,
+        Some(model::Feature::Node(_, ref mut tags, _)) => Some(tags),
         Some(model::Feature::Way(ref mut w)) => Some(&mut w.tags),
         Some(model::Feature::Relation(ref mut r)) => Some(&mut r.tags),
     }
@@ -438,61 +444,73 @@ mod tests {
                 id: -100,
                 nodes: vec![-1, -2],
                 tags: tags! {"highway": "primary", "ref": "-100"},
+                meta: None,
             },
             Way {
                 id: -107,
                 nodes: vec![-2, -61],
                 tags: tags! {"highway": "primary", "motor_vehicle": "no", "ref": "-107"},
+                meta: None,
             },
             Way {
                 id: -108,
                 nodes: vec![-63, -60, -61, -62, -63],
                 tags: tags! {"highway": "primary", "junction": "roundabout", "ref": "-108"},
+                meta: None,
             },
             Way {
                 id: -101,
                 nodes: vec![-2, -3],
                 tags: tags! {"highway": "unclassified", "ref": "-101"},
+                meta: None,
             },
             Way {
                 id: -102,
                 nodes: vec![-3, -7],
                 tags: tags! {"highway": "unclassified", "ref": "-102"},
+                meta: None,
             },
             Way {
                 id: -109,
                 nodes: vec![-7, -62],
                 tags: tags! {"highway": "unclassified", "ref": "-109"},
+                meta: None,
             },
             Way {
                 id: -110,
                 nodes: vec![-8, -7],
                 tags: tags! {"highway": "unclassified", "ref": "-110"},
+                meta: None,
             },
             Way {
                 id: -105,
                 nodes: vec![-7, -4],
                 tags: tags! {"highway": "unclassified", "oneway": "yes", "ref": "-105"},
+                meta: None,
             },
             Way {
                 id: -103,
                 nodes: vec![-4, -3],
                 tags: tags! {"highway": "motorway", "ref": "-103"},
+                meta: None,
             },
             Way {
                 id: -111,
                 nodes: vec![-63, -9],
                 tags: tags! {"highway": "primary", "ref": "-111"},
+                meta: None,
             },
             Way {
                 id: -104,
                 nodes: vec![-3, -5],
                 tags: tags! {"highway": "motorway", "ref": "-104"},
+                meta: None,
             },
             Way {
                 id: -106,
                 nodes: vec![-7, -5],
                 tags: tags! {"highway": "unclassified", "oneway": "-1", "ref": "-106"},
+                meta: None,
             },
         ]
     }
@@ -519,6 +537,7 @@ mod tests {
                     },
                 ],
                 tags: tags! {"ref": "-200", "restriction": "no_left_turn", "type": "restriction"},
+                meta: None,
             },
             Relation {
                 id: -201,
@@ -540,6 +559,7 @@ mod tests {
                     },
                 ],
                 tags: tags! {"ref": "-201", "restriction": "only_right_turn", "type": "restriction"},
+                meta: None,
             },
             Relation {
                 id: -202,
@@ -561,6 +581,7 @@ mod tests {
                     },
                 ],
                 tags: tags! {"except": "motorcar", "ref": "-202", "restriction": "no_left_turn", "type": "restriction"},
+                meta: None,
             },
         ];
     }
@@ -574,7 +595,8 @@ mod tests {
 
         for f in features {
             match f {
-                Ok(Feature::Node(n)) => nodes.push(n),
+                // SIMPLE_XML carries no node tags - checked separately by parse_node_tags.
+                Ok(Feature::Node(n, _, _)) => nodes.push(n),
                 Ok(Feature::Way(w)) => ways.push(w),
                 Ok(Feature::Relation(r)) => relations.push(r),
                 Err(e) => return Err(e),
@@ -601,4 +623,27 @@ mod tests {
     fn parse_from_io() -> Result<(), quick_xml::Error> {
         check_against_expected(Reader::from_io(io::Cursor::new(SIMPLE_XML)))
     }
+
+    #[test]
+    fn parse_node_tags() -> Result<(), quick_xml::Error> {
+        const DATA: &[u8] = br#"<osm>
+            <node id="1" lat="1.0" lon="2.0" />
+            <node id="2" lat="1.0" lon="2.0">
+                <tag k="barrier" v="gate" />
+                <tag k="access" v="no" />
+            </node>
+        </osm>"#;
+
+        let features = Reader::from_buffer(DATA)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|f| match f {
+                Feature::Node(n, tags, _) => (n.id, tags),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(features, vec![(1, tags! {}), (2, tags! {"barrier": "gate", "access": "no"})]);
+        Ok(())
+    }
 }
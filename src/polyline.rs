@@ -0,0 +1,153 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! [Google's Encoded Polyline Algorithm](https://developers.google.com/maps/documentation/utilities/polylinealgorithm) -
+//! a compact ASCII representation of a sequence of lat-lon coordinates, as commonly
+//! consumed by map rendering clients.
+
+use crate::Graph;
+
+/// Error which can occur while [decoding](decode) an encoded polyline string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The string ended in the middle of an encoded coordinate.
+    #[error("unexpected end of polyline")]
+    UnexpectedEnd,
+}
+
+/// Encodes a single signed delta (already scaled to integer units) as per the
+/// Encoded Polyline Algorithm: left-shift by one bit (and invert all bits if the
+/// original value was negative), then emit 5-bit groups, least-significant first,
+/// OR-ing every group but the last with `0x20` and adding `63` before pushing as ASCII.
+fn encode_value(value: i64, out: &mut String) {
+    let mut shifted = value << 1;
+    if value < 0 {
+        shifted = !shifted;
+    }
+
+    while shifted >= 0x20 {
+        out.push((((shifted & 0x1F) | 0x20) + 63) as u8 as char);
+        shifted >>= 5;
+    }
+    out.push((shifted + 63) as u8 as char);
+}
+
+/// Encodes the `(lat, lon)` positions of a route (as returned by
+/// [find_route](crate::find_route)) into a [Google Encoded Polyline](self) string.
+///
+/// `precision` is the number of decimal digits of precision to preserve - `5` for the
+/// standard polyline format, `6` for the higher-precision variant used by e.g. OSRM and
+/// Valhalla. Node ids not present in `g` are silently skipped.
+///
+/// Already the zig-zag/5-bit-chunk encoder a routing client expects from a route +
+/// [Graph] - see [simplify::douglas_peucker](crate::simplify::douglas_peucker) for shrinking
+/// the geometry before encoding it.
+pub fn encode(g: &Graph, route: &[i64], precision: u32) -> String {
+    let scale = 10i64.pow(precision) as f64;
+    let mut out = String::new();
+    let mut last_lat = 0i64;
+    let mut last_lon = 0i64;
+
+    for &node_id in route {
+        let Some(node) = g.get_node(node_id) else {
+            continue;
+        };
+
+        let lat = (node.lat as f64 * scale).round() as i64;
+        let lon = (node.lon as f64 * scale).round() as i64;
+
+        encode_value(lat - last_lat, &mut out);
+        encode_value(lon - last_lon, &mut out);
+
+        last_lat = lat;
+        last_lon = lon;
+    }
+
+    out
+}
+
+/// Decodes a single signed delta (in integer units) from `chars`, reversing the steps
+/// taken by [encode_value].
+fn decode_value(chars: &mut std::str::Chars) -> Result<i64, Error> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+
+    loop {
+        let c = chars.next().ok_or(Error::UnexpectedEnd)?;
+        let byte = (c as i64) - 63;
+        result |= (byte & 0x1F) << shift;
+        shift += 5;
+
+        if byte & 0x20 == 0 {
+            break;
+        }
+    }
+
+    Ok(if result & 1 != 0 { !(result >> 1) } else { result >> 1 })
+}
+
+/// Decodes a [Google Encoded Polyline](self) string into a sequence of `(lat, lon)`
+/// positions, reversing [encode]. `precision` must match the precision used to encode
+/// the string.
+pub fn decode(s: &str, precision: u32) -> Result<Vec<(f32, f32)>, Error> {
+    let scale = 10i64.pow(precision) as f64;
+    let mut chars = s.chars();
+    let mut points = Vec::new();
+    let mut lat = 0i64;
+    let mut lon = 0i64;
+
+    while chars.clone().next().is_some() {
+        lat += decode_value(&mut chars)?;
+        lon += decode_value(&mut chars)?;
+        points.push(((lat as f64 / scale) as f32, (lon as f64 / scale) as f32));
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn encode_google_example() {
+        // Example taken from Google's own documentation of the algorithm.
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 38.5, lon: -120.2 },
+                Node { id: 2, osm_id: 2, lat: 40.7, lon: -120.95 },
+                Node { id: 3, osm_id: 3, lat: 43.252, lon: -126.453 },
+            ],
+            [],
+        );
+
+        assert_eq!(encode(&g, &[1, 2, 3], 5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    }
+
+    #[test]
+    fn decode_google_example() {
+        let points = decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).unwrap();
+        assert_eq!(points, vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)]);
+    }
+
+    #[test]
+    fn round_trip_precision6() {
+        let g = Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 52.23024, lon: 21.01062 },
+                Node { id: 2, osm_id: 2, lat: 52.23852, lon: 21.0446 },
+            ],
+            [],
+        );
+
+        let encoded = encode(&g, &[1, 2], 6);
+        let decoded = decode(&encoded, 6).unwrap();
+        assert_eq!(decoded, vec![(52.23024, 21.01062), (52.23852, 21.0446)]);
+    }
+
+    #[test]
+    fn decode_unexpected_end() {
+        assert_eq!(decode("_p~iF~ps|U_ulLnnqC_mqNvxq`", 5), Err(Error::UnexpectedEnd));
+    }
+}
@@ -0,0 +1,137 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! Route geometry simplification using the
+//! [Ramer–Douglas–Peucker algorithm](https://en.wikipedia.org/wiki/Ramer%E2%80%93Douglas%E2%80%93Peucker_algorithm).
+
+use crate::Graph;
+
+/// Mean number of kilometers per degree of latitude - used to project lat-lon
+/// coordinates onto a local tangent plane for cheap perpendicular-distance checks.
+const KM_PER_DEGREE_LAT: f32 = 111.32;
+
+/// Projects a `(lat, lon)` position onto a local `(x, y)` plane, in kilometers,
+/// scaling longitude by the cosine of `ref_lat` to account for meridian convergence.
+fn project(lat: f32, lon: f32, ref_lat: f32) -> (f32, f32) {
+    let x = lon * KM_PER_DEGREE_LAT * ref_lat.to_radians().cos();
+    let y = lat * KM_PER_DEGREE_LAT;
+    (x, y)
+}
+
+/// Perpendicular distance, in kilometers, from `point` to the (infinite) line passing
+/// through `start` and `end`, all given as projected `(x, y)` coordinates.
+fn perpendicular_distance(point: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let len = (dx * dx + dy * dy).sqrt();
+
+    if len == 0.0 {
+        let (px, py) = (point.0 - start.0, point.1 - start.1);
+        return (px * px + py * py).sqrt();
+    }
+
+    ((point.0 - start.0) * dy - (point.1 - start.1) * dx).abs() / len
+}
+
+/// Recursively simplifies `positions[range]`, pushing the ids of retained points
+/// (other than `positions[range].last()`, which the caller is responsible for) onto
+/// `out`.
+fn simplify_range(positions: &[(i64, f32, f32)], epsilon_km: f32, out: &mut Vec<i64>) {
+    let (first_id, first_lat, first_lon) = positions[0];
+    let (_, last_lat, last_lon) = positions[positions.len() - 1];
+    let ref_lat = (first_lat + last_lat) * 0.5;
+
+    let start = project(first_lat, first_lon, ref_lat);
+    let end = project(last_lat, last_lon, ref_lat);
+
+    let mut farthest_index = 0;
+    let mut farthest_distance = 0.0_f32;
+
+    for (i, &(_, lat, lon)) in positions.iter().enumerate().take(positions.len() - 1).skip(1) {
+        let distance = perpendicular_distance(project(lat, lon, ref_lat), start, end);
+        if distance > farthest_distance {
+            farthest_distance = distance;
+            farthest_index = i;
+        }
+    }
+
+    if farthest_distance > epsilon_km {
+        simplify_range(&positions[..=farthest_index], epsilon_km, out);
+        simplify_range(&positions[farthest_index..], epsilon_km, out);
+    } else {
+        out.push(first_id);
+    }
+}
+
+/// Simplifies a route's geometry using the Douglas-Peucker algorithm, dropping
+/// intermediate nodes whose perpendicular distance from the chord between their
+/// neighbors is within `epsilon_km` (in the same units as
+/// [earth_distance](crate::earth_distance)).
+///
+/// Endpoints are always preserved. Routes with fewer than 3 nodes, or containing node
+/// ids absent from `g`, are returned unchanged.
+///
+/// Typically run before [encoding a route as a polyline](crate::polyline::encode), since a
+/// client rendering the geometry rarely needs every intermediate node [find_route](crate::find_route)
+/// returned.
+pub fn douglas_peucker(g: &Graph, route: &[i64], epsilon_km: f32) -> Vec<i64> {
+    if route.len() < 3 {
+        return route.to_vec();
+    }
+
+    let mut positions = Vec::with_capacity(route.len());
+    for &node_id in route {
+        let Some(node) = g.get_node(node_id) else {
+            return route.to_vec();
+        };
+        positions.push((node_id, node.lat, node.lon));
+    }
+
+    let mut out = Vec::new();
+    simplify_range(&positions, epsilon_km, &mut out);
+    out.push(route[route.len() - 1]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn almost_straight_fixture() -> Graph {
+        // A nearly-straight line with one point that barely deviates.
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0001, lon: 1.0 },
+                Node { id: 3, osm_id: 3, lat: 0.0, lon: 2.0 },
+            ],
+            [],
+        )
+    }
+
+    #[test]
+    fn drops_within_tolerance() {
+        let g = almost_straight_fixture();
+        assert_eq!(douglas_peucker(&g, &[1, 2, 3], 1.0), vec![1, 3]);
+    }
+
+    #[test]
+    fn keeps_outside_tolerance() {
+        let g = almost_straight_fixture();
+        assert_eq!(douglas_peucker(&g, &[1, 2, 3], 0.001), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn short_route_unchanged() {
+        let g = almost_straight_fixture();
+        assert_eq!(douglas_peucker(&g, &[1, 2], 1.0), vec![1, 2]);
+        assert_eq!(douglas_peucker(&g, &[], 1.0), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn unknown_node_returned_unchanged() {
+        let g = almost_straight_fixture();
+        assert_eq!(douglas_peucker(&g, &[1, 2, 999], 1.0), vec![1, 2, 999]);
+    }
+}
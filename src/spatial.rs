@@ -0,0 +1,74 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! A node id-oriented spatial index, bulk-loaded from a [Graph], for snapping
+//! caller-supplied coordinates to routable nodes. Built on top of [KDTree], which does
+//! the actual nearest-neighbor and radius work; this module just exposes node ids
+//! instead of whole [Nodes](Node), which is what most callers actually want.
+
+use crate::{Graph, KDTree};
+
+/// A spatial index over the canonical (`id == osm_id`) nodes of a [Graph].
+#[derive(Debug, Clone)]
+pub struct SpatialIndex(KDTree);
+
+impl SpatialIndex {
+    /// Bulk-loads every canonical node of `g` into a spatial index.
+    /// Returns `None` if `g` has no canonical nodes.
+    pub fn build(g: &Graph) -> Option<Self> {
+        KDTree::build_from_graph(g).map(Self)
+    }
+
+    /// Returns the id of the node closest to the given position, or `None` if the
+    /// index is empty.
+    pub fn nearest(&self, lat: f32, lon: f32) -> Option<i64> {
+        Some(self.0.find_nearest_node(lat, lon).id)
+    }
+
+    /// Returns the ids of every node within `radius_km` of the given position.
+    pub fn within_radius(&self, lat: f32, lon: f32, radius_km: f32) -> Vec<i64> {
+        self.0
+            .find_within_radius(lat, lon, radius_km)
+            .into_iter()
+            .map(|n| n.id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn graph_fixture() -> Graph {
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.00, lon: 0.00 },
+                Node { id: 2, osm_id: 2, lat: 0.00, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.00, lon: 10.0 },
+            ],
+            [],
+        )
+    }
+
+    #[test]
+    fn nearest() {
+        let index = SpatialIndex::build(&graph_fixture()).unwrap();
+        assert_eq!(index.nearest(0.0, -1.0), Some(1));
+        assert_eq!(index.nearest(0.0, 9.0), Some(3));
+    }
+
+    #[test]
+    fn within_radius() {
+        let index = SpatialIndex::build(&graph_fixture()).unwrap();
+        let mut found = index.within_radius(0.0, 0.0, 5.0);
+        found.sort();
+        assert_eq!(found, vec![1, 2]);
+    }
+
+    #[test]
+    fn empty_graph() {
+        assert!(SpatialIndex::build(&Graph::default()).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+// (c) Copyright 2025 Mikołaj Kuranowski
+// SPDX-License-Identifier: MIT
+
+//! Multi-waypoint trip optimization - finding a good visiting order for a set of
+//! waypoints, similar to [OSRM's Trip service](https://project-osrm.org/docs/v5.5.1/api/#trip-service).
+
+use crate::{find_route, AStarError, Graph};
+
+/// Up to and including this many nodes (start + waypoints), [optimize] falls back to exact
+/// brute-force enumeration of every permutation, rather than the farthest-insertion
+/// heuristic. `8` nodes means at most `7! = 5040` permutations to check.
+const BRUTE_FORCE_NODE_LIMIT: usize = 8;
+
+/// Sums the cost of every edge along a route, as found by [find_route]. `route` is empty
+/// when [find_route] couldn't reach the destination at all - that's represented as
+/// [f32::INFINITY], same as an unreachable pair in [matrix](crate::matrix), so orderings
+/// relying on it are never picked as best.
+fn route_cost(g: &Graph, route: &[i64]) -> f32 {
+    if route.is_empty() {
+        return f32::INFINITY;
+    }
+    route
+        .windows(2)
+        .map(|pair| g.get_edge(pair[0], pair[1]))
+        .sum()
+}
+
+/// Total cost of visiting `nodes[order]` in order, closing back to `nodes[order[0]]`
+/// if `round_trip`.
+fn tour_cost(costs: &[Vec<f32>], order: &[usize], round_trip: bool) -> f32 {
+    let mut total: f32 = order.windows(2).map(|w| costs[w[0]][w[1]]).sum();
+    if round_trip {
+        total += costs[*order.last().unwrap()][order[0]];
+    }
+    total
+}
+
+/// Finds the optimal visiting order of `1..costs.len()` (i.e. every node except the
+/// fixed start at index `0`) by exhaustively trying every permutation.
+fn brute_force_order(costs: &[Vec<f32>], round_trip: bool) -> Vec<usize> {
+    let mut waypoints: Vec<usize> = (1..costs.len()).collect();
+    let mut best = {
+        let mut order = vec![0];
+        order.extend(&waypoints);
+        order
+    };
+    let mut best_cost = tour_cost(costs, &best, round_trip);
+
+    // Heap's algorithm, iterative - generates every permutation of `waypoints`.
+    let k = waypoints.len();
+    let mut c = vec![0usize; k];
+    let mut i = 0;
+    while i < k {
+        if c[i] < i {
+            if i % 2 == 0 {
+                waypoints.swap(0, i);
+            } else {
+                waypoints.swap(c[i], i);
+            }
+
+            let mut order = vec![0];
+            order.extend(&waypoints);
+            let cost = tour_cost(costs, &order, round_trip);
+            if cost < best_cost {
+                best_cost = cost;
+                best = order;
+            }
+
+            c[i] += 1;
+            i = 0;
+        } else {
+            c[i] = 0;
+            i += 1;
+        }
+    }
+
+    best
+}
+
+/// Builds an initial tour with farthest insertion: start with the node farthest from
+/// the fixed start, then repeatedly insert the unvisited node whose minimum distance to
+/// any node already in the tour is the largest, at the position in the tour minimizing
+/// the added cost.
+fn farthest_insertion_order(costs: &[Vec<f32>], round_trip: bool) -> Vec<usize> {
+    let n = costs.len();
+    let mut tour: Vec<usize> = vec![0];
+    let mut remaining: Vec<usize> = (1..n).collect();
+
+    if remaining.is_empty() {
+        return tour;
+    }
+
+    let seed = *remaining
+        .iter()
+        .max_by(|&&a, &&b| costs[0][a].partial_cmp(&costs[0][b]).unwrap())
+        .unwrap();
+    tour.push(seed);
+    remaining.retain(|&x| x != seed);
+
+    while !remaining.is_empty() {
+        let next = *remaining
+            .iter()
+            .max_by(|&&a, &&b| {
+                let min_to_a = tour
+                    .iter()
+                    .map(|&t| costs[t][a].min(costs[a][t]))
+                    .fold(f32::INFINITY, f32::min);
+                let min_to_b = tour
+                    .iter()
+                    .map(|&t| costs[t][b].min(costs[b][t]))
+                    .fold(f32::INFINITY, f32::min);
+                min_to_a.partial_cmp(&min_to_b).unwrap()
+            })
+            .unwrap();
+        remaining.retain(|&x| x != next);
+
+        // Insertion positions are the edges between consecutive tour nodes; for an
+        // open path the edge closing the tour back to the start doesn't exist.
+        let edge_count = if round_trip { tour.len() } else { tour.len() - 1 };
+        let mut best_pos = 1;
+        let mut best_added = f32::INFINITY;
+        for i in 0..edge_count {
+            let a = tour[i];
+            let b = tour[(i + 1) % tour.len()];
+            let added = costs[a][next] + costs[next][b] - costs[a][b];
+            if added < best_added {
+                best_added = added;
+                best_pos = i + 1;
+            }
+        }
+        tour.insert(best_pos, next);
+    }
+
+    tour
+}
+
+/// Improves a tour with repeated [2-opt](https://en.wikipedia.org/wiki/2-opt) edge
+/// swaps (keeping the fixed start at position `0`) until no swap reduces the total
+/// cost.
+fn two_opt(costs: &[Vec<f32>], mut order: Vec<usize>, round_trip: bool) -> Vec<usize> {
+    let n = order.len();
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+
+        for i in 1..n.saturating_sub(1) {
+            for j in (i + 1)..n {
+                let mut candidate = order.clone();
+                candidate[i..=j].reverse();
+
+                if tour_cost(costs, &candidate, round_trip) < tour_cost(costs, &order, round_trip)
+                {
+                    order = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// Finds a good visiting order for `waypoints`, starting at `start`, and returns the
+/// concatenated node-id path through all of them in that order.
+///
+/// Builds a full pairwise cost matrix with [find_route], then solves the ordering:
+/// exact brute-force enumeration for small waypoint counts, otherwise a
+/// farthest-insertion construction followed by 2-opt improvement. If `round_trip` is
+/// set, the returned path also returns to `start` at the end.
+///
+/// Up to [BRUTE_FORCE_NODE_LIMIT] nodes the exact branch enumerates permutations directly rather
+/// than a Held-Karp DP table - asymptotically worse, but simpler, and the node count is
+/// small enough that it doesn't matter.
+///
+/// Returns [AStarError::Unreachable] if no ordering of `waypoints` connects every leg.
+pub fn optimize(
+    g: &Graph,
+    start: i64,
+    waypoints: &[i64],
+    round_trip: bool,
+    step_limit: usize,
+) -> Result<Vec<i64>, AStarError> {
+    if waypoints.is_empty() {
+        return Ok(vec![start]);
+    }
+
+    let mut nodes = Vec::with_capacity(waypoints.len() + 1);
+    nodes.push(start);
+    nodes.extend_from_slice(waypoints);
+    let n = nodes.len();
+
+    let mut paths = vec![vec![None; n]; n];
+    let mut costs = vec![vec![0.0_f32; n]; n];
+
+    for i in 0..n {
+        for j in 0..n {
+            if i == j {
+                continue;
+            }
+            let route = find_route(g, nodes[i], nodes[j], step_limit, None)?;
+            costs[i][j] = route_cost(g, &route);
+            paths[i][j] = Some(route);
+        }
+    }
+
+    let order = if n <= BRUTE_FORCE_NODE_LIMIT {
+        brute_force_order(&costs, round_trip)
+    } else {
+        two_opt(&costs, farthest_insertion_order(&costs, round_trip), round_trip)
+    };
+
+    let mut legs: Vec<(usize, usize)> = order.windows(2).map(|w| (w[0], w[1])).collect();
+    if round_trip {
+        legs.push((*order.last().unwrap(), order[0]));
+    }
+    if let Some(&(i, j)) = legs.iter().find(|&&(i, j)| costs[i][j].is_infinite()) {
+        return Err(AStarError::Unreachable(nodes[i], nodes[j]));
+    }
+
+    let mut result = vec![nodes[order[0]]];
+    for &(i, j) in &legs {
+        let mut segment = paths[i][j].clone().unwrap();
+        segment.remove(0);
+        result.extend(segment);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[inline]
+    fn square_fixture() -> Graph {
+        // A unit square with nodes at its corners, fully connected both ways.
+        //
+        // 4───────3
+        // │       │
+        // │       │
+        // 1───────2
+        Graph::from_iter(
+            [
+                Node { id: 1, osm_id: 1, lat: 0.0, lon: 0.0 },
+                Node { id: 2, osm_id: 2, lat: 0.0, lon: 0.01 },
+                Node { id: 3, osm_id: 3, lat: 0.01, lon: 0.01 },
+                Node { id: 4, osm_id: 4, lat: 0.01, lon: 0.0 },
+            ],
+            [
+                (1, 2, 100.0),
+                (2, 1, 100.0),
+                (2, 3, 100.0),
+                (3, 2, 100.0),
+                (3, 4, 100.0),
+                (4, 3, 100.0),
+                (4, 1, 100.0),
+                (1, 4, 100.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn visits_all_waypoints() {
+        let g = square_fixture();
+        let route = optimize(&g, 1, &[3, 2, 4], false, 100).unwrap();
+        // The optimal order traverses the perimeter without crossing the square.
+        assert_eq!(route, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn round_trip_returns_to_start() {
+        let g = square_fixture();
+        let route = optimize(&g, 1, &[3, 2, 4], true, 100).unwrap();
+        assert_eq!(route, vec![1, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn no_waypoints() {
+        let g = square_fixture();
+        assert_eq!(optimize(&g, 1, &[], false, 100), Ok(vec![1]));
+    }
+
+    #[test]
+    fn unreachable_waypoint_is_rejected() {
+        // Node 5 is isolated - no ordering of waypoints can reach it.
+        let mut g = square_fixture();
+        g.set_node(Node { id: 5, osm_id: 5, lat: 1.0, lon: 1.0 });
+
+        assert_eq!(
+            optimize(&g, 1, &[2, 5], false, 100),
+            Err(AStarError::Unreachable(2, 5))
+        );
+    }
+}